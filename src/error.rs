@@ -25,7 +25,7 @@ pub enum DssErrorGroup {
     UNKNOWN,
 }
 
-#[derive(Debug,Clone)]
+#[derive(Debug,PartialEq,Clone)]
 pub enum DssErrorKind {
     STATUS_OK,
     INVALID_FILE_VERSION,
@@ -219,6 +219,31 @@ impl DssError {
         err
     }
 
+    pub(crate) fn raise_kind(kind:DssErrorKind,mesg:String) -> Self {
+        let mut err = DssError::raise(mesg);
+        err.group = DssErrorGroup::ACCESS;
+        err.kind = kind;
+        err
+    }
+
+    pub fn kind(&self) -> &DssErrorKind {
+        &self.kind
+    }
+
+    pub fn message(&self) -> &str {
+        self.mesg.trim_end_matches('\0')
+    }
+
+    // Pathname and file address of the last heclib error, if any
+    pub fn last_pathname(&self) -> Option<String> {
+        let path = unsafe {CStr::from_ptr(self.c_obj.lastPathname.as_ptr())}.to_string_lossy().trim().to_string();
+        if path.is_empty() {None} else {Some(path)}
+    }
+
+    pub fn last_address(&self) -> Option<i64> {
+        if self.c_obj.lastAddress > 0 {Some(self.c_obj.lastAddress)} else {None}
+    }
+
     pub fn update(mut self) -> Self {
         // zerrorclear?
         let errobj_ptr = Box::into_raw(self.c_obj);
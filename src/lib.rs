@@ -2,11 +2,12 @@ use hecdss_sys::*;
 use std::io::prelude::*;
 use std::{self,mem,str};
 use std::ffi::{CStr,CString};
+use std::collections::{HashMap,HashSet};
 use std::error::Error;
 use std::os::raw::*;
 use regex::Regex;
 pub mod error;
-use error::{DssResult,DssError};
+use error::{DssResult,DssError,DssErrorKind};
 
 #[cfg(feature = "threadsafe")]
 use std::sync::Mutex;
@@ -22,9 +23,11 @@ pub struct HecDss {
     ifltab: [i64;500],
     filename: String,
     version: i32,
+    // false once the file could not be reopened after a squeeze
+    open: bool,
 }
 
-#[derive(Debug,Clone)]
+#[derive(Debug,PartialEq,Clone)]
 pub struct DssPathname {
     apart:Option<String>,
     bpart:Option<String>,
@@ -34,6 +37,11 @@ pub struct DssPathname {
     fpart:Option<String>
 }
 
+#[derive(Debug,PartialEq,Clone)]
+pub struct PathnamePattern {
+    parts:Vec<String>
+}
+
 #[derive(Debug,Copy,Clone)]
 pub struct HecTime {
     value: c_int,
@@ -68,6 +76,7 @@ pub enum HecTimeInterval {
     year,
 }
 
+#[derive(Debug,PartialEq,Copy,Clone)]
 pub enum HecTimeIntervalIreg {
     day,
     month,
@@ -96,21 +105,102 @@ pub enum DataUnit<'a> {
     undefined(&'a str)
 }
 
+#[derive(Debug,PartialEq,Clone)]
+pub enum DataValues {
+    float(Vec<f32>),
+    double(Vec<f64>)
+}
+
+#[derive(Debug,PartialEq,Copy,Clone)]
+pub struct QualityFlag {
+    value:c_int
+}
+
+#[derive(Debug,PartialEq,Copy,Clone)]
+pub enum QualityValidity {
+    unknown,
+    okay,
+    missing,
+    questionable,
+    rejected
+}
+
+#[derive(Debug,PartialEq,Copy,Clone)]
+pub enum QualityReplacementCause {
+    none,
+    automatic,
+    interactive,
+    manual,
+    restored,
+    undefined(c_int)
+}
+
+#[derive(Debug,PartialEq,Copy,Clone)]
+pub enum QualityReplacementMethod {
+    none,
+    linear_interpolation,
+    explicit,
+    missing,
+    graphical,
+    undefined(c_int)
+}
+
 #[derive(Debug,PartialEq,Copy,Clone)]
 pub enum TimeSeriesType {
     regular,
     irregular
 }
 
+#[derive(Debug,PartialEq,Copy,Clone)]
+pub enum StoreMode {
+    // regular
+    replace_all,              // flag 0, always replace stored data
+    replace_missing_only,     // flag 1, only replace stored missing values
+    write_all_missing,        // flag 2, write even if all values are missing
+    delete_if_all_missing,    // flag 3, do not write an all missing block and delete it if stored
+    replace_with_non_missing, // flag 4, missing input values do not replace stored ones
+    // irregular
+    merge,                    // flag 0, merge with stored data
+    replace_window            // flag 1, delete stored data in the time window then write
+}
+
 #[derive(Debug,Clone)]
-pub struct TimeSeriesContainer<'a> {
+pub struct TimeSeriesContainer {
     // all
     ts_type:TimeSeriesType,
     pathname:Option<DssPathname>,
-    values:Vec<f32>,
+    values:DataValues,
+    // meta data
+    data_unit:String,
+    data_type:String,
+    quality:Option<Vec<c_int>>,
+    quality_element_size:c_int,
+    // notes, either integer or character but not both
+    inotes:Option<Vec<c_int>>,
+    inote_element_size:c_int,
+    cnotes:Option<Vec<String>>,
+    time_zone:Option<String>,
+    time_offset:c_int,
+    precision:Option<c_int>,
+    user_header:Option<Vec<c_int>>,
+    // for irregular
+    times:Option<Vec<HecTime>>,
+    // for regular series only
+    start_time:Option<HecTime>,
+    interval:Option<HecTimeInterval>
+}
+
+#[derive(Debug,Clone)]
+pub struct TimeSeriesProfile {
+    ts_type:TimeSeriesType,
+    pathname:Option<DssPathname>,
+    // depths[number_depths] and values[number_times][number_depths]
+    depths:DataValues,
+    values:DataValues,
     // meta data
-    data_unit:DataUnit<'a>,
-    data_type:DataType<'a>,
+    depth_unit:String,
+    value_unit:String,
+    data_type:String,
     // for irregular
     times:Option<Vec<HecTime>>,
     // for regular series only
@@ -118,6 +208,18 @@ pub struct TimeSeriesContainer<'a> {
     interval:Option<HecTimeInterval>
 }
 
+#[derive(Debug)]
+pub struct TimeSeriesIter<'a> {
+    dss:&'a mut HecDss,
+    pathname:CString,
+    block_size:c_int,
+    // current block start and overall window as (julian, seconds)
+    block_julian:c_int,
+    start:(c_int,c_int),
+    end:(c_int,c_int),
+    done:bool
+}
+
 #[derive(Debug)]
 pub struct TimeSeriesOptions {
     slice:Option<TimeSeriesSlice>,
@@ -133,24 +235,46 @@ pub struct TimeSeriesSlice {
 }
 
 #[derive(Debug)]
-pub struct PairedDataTable<'a> {
+pub struct PairedDataTable {
     pathname:Option<DssPathname>,
     shape:(c_int,c_int),
-    headers:Option<Vec<&'a str>>,
-    index:Vec<f32>,
-    columns:Vec<Vec<f32>>,
+    headers:Option<Vec<String>>,
+    index:DataValues,
+    columns:Vec<DataValues>,
     // meta data
-    //data_unit:DataUnit<'a>,
-    //data_type:DataType<'a>,
-    index_unit:DataUnit<'a>,
-    index_type:DataType<'a>,
-    column_unit:DataUnit<'a>,
-    column_type:DataType<'a>
+    index_unit:String,
+    index_type:String,
+    column_unit:String,
+    column_type:String,
+    index_is_xaxis:bool,
+    // (index,columns) decimal places
+    precision:Option<(c_int,c_int)>,
+    time_zone:Option<String>
+}
+
+#[derive(Debug,PartialEq,Copy,Clone)]
+pub enum InterpolationMethod {
+    linear,
+    log_log
+}
+
+#[derive(Debug,PartialEq,Copy,Clone)]
+pub enum OutOfRange {
+    error,
+    clamp,
+    extrapolate
+}
+
+#[derive(Debug,Copy,Clone)]
+pub struct LookupOptions {
+    method:InterpolationMethod,
+    out_of_range:OutOfRange
 }
 
 #[derive(Debug)]
 pub struct PairedDataOptions {
     slice:PairedDataSlice,
+    as_double:Option<bool>
 }
 #[derive(Debug)]
 pub struct PairedDataSlice {
@@ -164,12 +288,166 @@ pub struct PairedDataSlice {
 pub struct DssMetaData {
 }
 
+#[derive(Debug,PartialEq,Copy,Clone)]
+pub enum RecordType {
+    rts,
+    rts_pattern,
+    rts_profile,
+    rtd,
+    rtd_pattern,
+    rtd_profile,
+    its,
+    its_pattern,
+    its_profile,
+    itd,
+    itd_pattern,
+    itd_profile,
+    pd,
+    pdd,
+    text,
+    text_table,
+    grid(c_int),
+    tin,
+    location,
+    array,
+    file,
+    image,
+    undefined(c_int)
+}
+
+#[derive(Debug)]
+pub struct CatalogOptions {
+    filter:Option<PathnamePattern>,
+    sorted:bool,
+    include_dates:bool,
+    include_crc:bool
+}
+
+#[derive(Debug,Clone)]
+pub struct CatalogEntry {
+    pathname:DssPathname,
+    record_type:RecordType,
+    // julian dates of first and last data
+    dates:Option<(c_int,c_int)>,
+    // milliseconds since 1970
+    last_write_time:Option<i64>,
+    crc:Option<c_uint>
+}
+
+#[derive(Debug)]
+pub struct ChangeTracker {
+    baseline:*mut zStructCatalog,
+    // file the snapshot was taken from
+    filename:String,
+    pathnames:HashSet<String>,
+    filter:Option<PathnamePattern>,
+    use_crc:bool
+}
+
+#[derive(Debug,PartialEq,Copy,Clone)]
+pub enum ChangeKind {
+    added,
+    modified
+}
+
+#[derive(Debug,Clone)]
+pub struct RecordChange {
+    pathname:DssPathname,
+    kind:ChangeKind
+}
+
+#[derive(Debug,Clone)]
+pub struct RecordInfo {
+    pathname:DssPathname,
+    record_type:RecordType,
+    version:c_int,
+    number_values:c_int,
+    logical_number_values:c_int,
+    allocated_size:c_int,
+    // milliseconds since 1970
+    last_write_time:i64,
+    creation_time:i64,
+    program:String,
+    ts_size:Option<TimeSeriesRecordSize>,
+    pd_size:Option<PairedDataRecordSize>
+}
+
+#[derive(Debug,Clone)]
+pub struct TimeSeriesRecordSize {
+    precision:c_int,
+    time_offset:c_int,
+    profile_depths:c_int,
+    block_start:c_int,
+    block_end:c_int,
+    value_size:c_int,
+    value_element_size:c_int,
+    quality_element_size:c_int,
+    inote_element_size:c_int,
+    cnotes_length:c_int
+}
+
+#[derive(Debug,Clone)]
+pub struct PairedDataRecordSize {
+    number_curves:c_int,
+    number_ordinates:c_int,
+    value_size:c_int,
+    independent_is_xaxis:bool,
+    labels_length:c_int,
+    precision:c_int
+}
+
+#[derive(Debug,PartialEq,Copy,Clone)]
+pub enum IntegrityCheck {
+    file,
+    hash_table,
+    links,
+    pathnames
+}
+
+// Summary of one failed check. heclib only keeps the last error it raised,
+// so a check that finds several problems is reported by the last of them.
+#[derive(Debug,Clone)]
+pub struct IntegrityIssue {
+    check:IntegrityCheck,
+    kind:DssErrorKind,
+    message:String,
+    last_pathname:Option<String>,
+    last_address:Option<i64>
+}
+
+#[derive(Debug,Clone)]
+pub struct IntegrityReport {
+    // check and the number of errors it reported
+    checks:Vec<(IntegrityCheck,c_int)>,
+    issues:Vec<IntegrityIssue>
+}
+
+#[derive(Debug,Clone)]
+pub struct CondensedPathname {
+    pathname:DssPathname,
+    first:DssPathname,
+    last:DssPathname,
+    record_type:RecordType,
+    block_count:usize,
+    // first and last time of the whole record set, time-series only
+    time_range:Option<(HecTime,HecTime)>
+}
+
 pub fn config_dss_logging(group:c_int,level:c_int) {
     unsafe {
         zsetMessageLevel(group,level);
     }
 }
 
+// Retrieve flag of ztsRetrieve/zpdRetrieve: 0 = as stored, 1 = floats, 2 = doubles
+fn float_or_double_flag(as_double:Option<bool>) -> c_int {
+    match as_double {
+        Some(true) => 2,
+        Some(false) => 1,
+        _ => 0
+    }
+}
+
 impl HecTimeGranularity {
     pub fn default() -> Self {
         HecTimeGranularity::minute
@@ -231,7 +509,8 @@ impl HecTimeInterval {
         let period2 = ["Week","Month","Semi-Month","Tri-Month","Year"];
         let re = Regex::new(r"^(\d*)(.*)").unwrap();
         let caps = re.captures(interval).unwrap();
-        let num = caps.get(1).map_or(0,|x| x.as_str().parse::<c_int>().unwrap());
+        // no numeric prefix for Week, Month, Semi-Month, Tri-Month and Year
+        let num = caps.get(1).map_or(0,|x| x.as_str().parse::<c_int>().unwrap_or(0));
         let text = caps.get(2).map_or("",|x| x.as_str());
         if num > 0 && !text.is_empty() {
             if text.eq_ignore_ascii_case("Second") {
@@ -243,7 +522,9 @@ impl HecTimeInterval {
             }else if text.eq_ignore_ascii_case("Day") {
                 return Some(HecTimeInterval::day(num))
             } else {};
-        } else {
+        };
+        // calendar intervals are written either bare (Semi-Month) or with a 1 (1Month)
+        if num <= 1 {
             if !text.is_empty() {
                 if text.eq_ignore_ascii_case("Week") {
                     return Some(HecTimeInterval::week)
@@ -278,6 +559,41 @@ impl HecTimeInterval {
     }
 }
 
+impl HecTimeIntervalIreg {
+    pub fn to_string(&self) -> String {
+        match *self {
+            HecTimeIntervalIreg::day => "IR-Day".to_string(),
+            HecTimeIntervalIreg::month => "IR-Month".to_string(),
+            HecTimeIntervalIreg::year => "IR-Year".to_string(),
+            HecTimeIntervalIreg::decade => "IR-Decade".to_string(),
+            HecTimeIntervalIreg::century => "IR-Century".to_string(),
+        }
+    }
+
+    pub fn from_string(interval:&str) -> Option<Self> {
+        let value = interval.trim().to_lowercase();
+        match value.as_str() {
+            "ir-day" => Some(HecTimeIntervalIreg::day),
+            "ir-month" => Some(HecTimeIntervalIreg::month),
+            "ir-year" => Some(HecTimeIntervalIreg::year),
+            "ir-decade" => Some(HecTimeIntervalIreg::decade),
+            "ir-century" => Some(HecTimeIntervalIreg::century),
+            _ => None
+        }
+    }
+
+    fn block_size(&self) -> c_int {
+        // block size codes used by heclib (DAILY_BLOCK ... CENTURY_BLOCK)
+        match *self {
+            HecTimeIntervalIreg::day => 1,
+            HecTimeIntervalIreg::month => 2,
+            HecTimeIntervalIreg::year => 3,
+            HecTimeIntervalIreg::decade => 4,
+            HecTimeIntervalIreg::century => 5,
+        }
+    }
+}
+
 impl HecTime {
     pub fn new(value:c_int,granularity:Option<HecTimeGranularity>,basedate:Option<HecBaseDate>) -> Self {
         let gran = match granularity {
@@ -349,6 +665,24 @@ impl HecTime {
         }
     }
 
+    fn from_julian_seconds(julian:c_int,seconds:c_int,granularity:HecTimeGranularity,basedate_days:c_int) -> Self {
+        let total = ((julian - basedate_days) as i64)*86400 + (seconds as i64);
+        let value = (total/(granularity.value() as i64)) as c_int;
+        HecTime{value:value,granularity:granularity,basedate_days:basedate_days}
+    }
+
+    fn to_julian_seconds(&self) -> (c_int,c_int) {
+        // DSS represents midnight as second 86400 of the previous day
+        let total = (self.value as i64)*(self.granularity.value() as i64);
+        let mut days = total.div_euclid(86400);
+        let mut seconds = total.rem_euclid(86400);
+        if seconds == 0 {
+            days -= 1;
+            seconds = 86400;
+        }
+        (self.basedate_days + days as c_int, seconds as c_int)
+    }
+
     pub fn add_seconds(&mut self,seconds:c_int) { //-> Result<(),Box<dyn Error>>{
         let value = seconds/&self.granularity.value();
         self.value = self.value + value;
@@ -466,19 +800,162 @@ impl TimeSeriesType {
     }
 }    
 
-impl <'a> TimeSeriesContainer<'a> {
+impl StoreMode {
+    pub fn default(ts_type:TimeSeriesType) -> Self {
+        match ts_type {
+            TimeSeriesType::regular => StoreMode::replace_all,
+            TimeSeriesType::irregular => StoreMode::merge,
+        }
+    }
+
+    pub fn value(&self,ts_type:TimeSeriesType) -> DssResult<c_int> {
+        // storage flag of ztsStore, the meaning of which depends on the time-series type
+        match (ts_type,*self) {
+            (TimeSeriesType::regular,StoreMode::replace_all) => Ok(0),
+            (TimeSeriesType::regular,StoreMode::replace_missing_only) => Ok(1),
+            (TimeSeriesType::regular,StoreMode::write_all_missing) => Ok(2),
+            (TimeSeriesType::regular,StoreMode::delete_if_all_missing) => Ok(3),
+            (TimeSeriesType::regular,StoreMode::replace_with_non_missing) => Ok(4),
+            (TimeSeriesType::irregular,StoreMode::merge) => Ok(0),
+            (TimeSeriesType::irregular,StoreMode::replace_window) => Ok(1),
+            (ts_type,mode) => Err(DssError::raise(format!("Store mode {:?} is not valid for {:?} time-series",mode,ts_type)))
+        }
+    }
+}
+
+impl DataValues {
+    pub fn len(&self) -> usize {
+        match self {
+            DataValues::float(x) => x.len(),
+            DataValues::double(x) => x.len()
+        }
+    }
+
+    pub fn is_double(&self) -> bool {
+        match self {
+            DataValues::float(_) => false,
+            DataValues::double(_) => true
+        }
+    }
+
+    pub fn as_float(&self) -> Option<&[f32]> {
+        match self {
+            DataValues::float(x) => Some(x.as_slice()),
+            _ => None
+        }
+    }
+
+    pub fn as_double(&self) -> Option<&[f64]> {
+        match self {
+            DataValues::double(x) => Some(x.as_slice()),
+            _ => None
+        }
+    }
+
+    pub fn to_double(&self) -> Vec<f64> {
+        match self {
+            DataValues::float(x) => x.iter().map(|v| *v as f64).collect(),
+            DataValues::double(x) => x.clone()
+        }
+    }
+}
+
+impl QualityFlag {
+    // Bit layout of the standard HEC quality word
+    const SCREENED:c_int = 1;
+    const OKAY:c_int = 1 << 1;
+    const MISSING:c_int = 1 << 2;
+    const QUESTIONABLE:c_int = 1 << 3;
+    const REJECTED:c_int = 1 << 4;
+    const RANGE_SHIFT:c_int = 5;
+    const DIFFERENT_VALUE:c_int = 1 << 7;
+    const REPLACE_CAUSE_SHIFT:c_int = 8;
+    const REPLACE_METHOD_SHIFT:c_int = 11;
+    const PROTECTED:c_int = 1 << 31;
+
+    pub fn new(value:c_int) -> Self {
+        QualityFlag{value:value}
+    }
+
+    pub fn value(&self) -> c_int {
+        self.value
+    }
+
+    pub fn is_screened(&self) -> bool {
+        self.value & QualityFlag::SCREENED != 0
+    }
+
+    pub fn validity(&self) -> QualityValidity {
+        if self.value & QualityFlag::REJECTED != 0 {
+            QualityValidity::rejected
+        } else if self.value & QualityFlag::QUESTIONABLE != 0 {
+            QualityValidity::questionable
+        } else if self.value & QualityFlag::MISSING != 0 {
+            QualityValidity::missing
+        } else if self.value & QualityFlag::OKAY != 0 {
+            QualityValidity::okay
+        } else {
+            QualityValidity::unknown
+        }
+    }
+
+    pub fn range(&self) -> c_int {
+        // 0 = no range, 1 - 3 = range of value exceeded the 1st - 3rd limit
+        (self.value >> QualityFlag::RANGE_SHIFT) & 0b11
+    }
+
+    pub fn is_changed(&self) -> bool {
+        self.value & QualityFlag::DIFFERENT_VALUE != 0
+    }
+
+    pub fn replacement_cause(&self) -> QualityReplacementCause {
+        match (self.value >> QualityFlag::REPLACE_CAUSE_SHIFT) & 0b111 {
+            0 => QualityReplacementCause::none,
+            1 => QualityReplacementCause::automatic,
+            2 => QualityReplacementCause::interactive,
+            3 => QualityReplacementCause::manual,
+            4 => QualityReplacementCause::restored,
+            x => QualityReplacementCause::undefined(x)
+        }
+    }
+
+    pub fn replacement_method(&self) -> QualityReplacementMethod {
+        match (self.value >> QualityFlag::REPLACE_METHOD_SHIFT) & 0b1111 {
+            0 => QualityReplacementMethod::none,
+            1 => QualityReplacementMethod::linear_interpolation,
+            2 => QualityReplacementMethod::explicit,
+            3 => QualityReplacementMethod::missing,
+            4 => QualityReplacementMethod::graphical,
+            x => QualityReplacementMethod::undefined(x)
+        }
+    }
+
+    pub fn is_protected(&self) -> bool {
+        self.value & QualityFlag::PROTECTED != 0
+    }
+}
+
+impl TimeSeriesContainer {
     pub fn new(ts_type:TimeSeriesType,num_values:c_int) -> Self {
+        let values = DataValues::float(vec![0f32;num_values as usize]);
+        TimeSeriesContainer::with_values(ts_type,values)
+    }
+
+    pub fn new_double(ts_type:TimeSeriesType,num_values:c_int) -> Self {
+        let values = DataValues::double(vec![0f64;num_values as usize]);
+        TimeSeriesContainer::with_values(ts_type,values)
+    }
+
+    fn with_values(ts_type:TimeSeriesType,values:DataValues) -> Self {
+        let num_values = values.len();
         let mut pathname:Option<DssPathname>= None;
-        let mut values = vec![0f32;num_values as usize]; //Vec::<f32>::with_capacity(num_values as usize);
         let mut times :Option<Vec<HecTime>>= None;
-        let mut unit = DataUnit::undefined("");
-        let mut dtype = DataType::undefined("");
         let mut start_time:Option<HecTime> = None;
         let mut interval:Option<HecTimeInterval> = None;
 
         match ts_type {
             TimeSeriesType::irregular => {
-                times = Some(vec![HecTime::new(0,None,None);num_values as usize]);
+                times = Some(vec![HecTime::new(0,None,None);num_values]);
 
             },
             TimeSeriesType::regular => {
@@ -489,16 +966,30 @@ impl <'a> TimeSeriesContainer<'a> {
         TimeSeriesContainer{ts_type:ts_type,
                             pathname:pathname,
                             values:values,
-                            data_unit:unit,
-                            data_type:dtype,
+                            data_unit:String::new(),
+                            data_type:String::new(),
+                            quality:None,
+                            quality_element_size:0,
+                            inotes:None,
+                            inote_element_size:0,
+                            cnotes:None,
+                            time_zone:None,
+                            time_offset:0,
+                            precision:None,
+                            user_header:None,
                             times:times,
                             start_time:start_time,
                             interval:interval}
     }
 
+    // For regular series the interval is taken from the E-part
     pub fn set_pathname(&mut self, path:Option<DssPathname>) {
+        if self.ts_type == TimeSeriesType::regular {
+            if let Some(interval) = path.as_ref().and_then(|x| x.epart.as_ref()).and_then(|x| HecTimeInterval::from_string(x)) {
+                self.interval = Some(interval);
+            }
+        }
         self.pathname = path;
-        //
     }
 
     pub fn pathname(&self) -> Option<DssPathname> {
@@ -515,19 +1006,113 @@ impl <'a> TimeSeriesContainer<'a> {
     }
 
     pub fn set_values(&mut self, values:&[f32]) -> DssResult<()> {
-        let capacity = self.values.len();
-        let length = values.len();
-        match length {
-            capacity => {
-                self.values.clone_from_slice(values);
-                Ok(())
+        if values.len() != self.values.len() {
+            Err(DssError::raise("The length of the value is not equal to TimeSeriesContainer capacity".to_string()))?
+        }
+        self.values = DataValues::float(values.to_vec());
+        Ok(())
+    }
+
+    pub fn set_double_values(&mut self, values:&[f64]) -> DssResult<()> {
+        if values.len() != self.values.len() {
+            Err(DssError::raise("The length of the value is not equal to TimeSeriesContainer capacity".to_string()))?
+        }
+        self.values = DataValues::double(values.to_vec());
+        Ok(())
+    }
+
+    pub fn values(&self) -> &DataValues {
+        &self.values
+    }
+
+    pub fn is_double(&self) -> bool {
+        self.values.is_double()
+    }
+
+    pub fn set_quality(&mut self, flags:Option<&[QualityFlag]>) -> DssResult<()> {
+        match flags {
+            Some(x) => {
+                let values:Vec<c_int> = x.iter().map(|f| f.value()).collect();
+                self.set_quality_raw(Some(values.as_slice()),1)
+            },
+            _ => self.set_quality_raw(None,0)
+        }
+    }
+
+    pub fn quality(&self) -> Option<Vec<QualityFlag>> {
+        // Only the first word of each element carries the standard HEC quality bits
+        let size = self.quality_element_size.max(1) as usize;
+        self.quality.as_ref().map(|x| x.iter().step_by(size).map(|v| QualityFlag::new(*v)).collect())
+    }
+
+    pub fn set_quality_raw(&mut self, values:Option<&[c_int]>, element_size:c_int) -> DssResult<()> {
+        match values {
+            Some(x) => {
+                if element_size < 1 || x.len() != self.values.len()*(element_size as usize) {
+                    Err(DssError::raise(format!("The length of the quality array is not equal to TimeSeriesContainer capacity times element size {}",element_size)))?
+                }
+                self.quality = Some(x.to_vec());
+                self.quality_element_size = element_size;
+            },
+            _ => {
+                self.quality = None;
+                self.quality_element_size = 0;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn quality_raw(&self) -> Option<&[c_int]> {
+        self.quality.as_ref().map(|x| x.as_slice())
+    }
+
+    pub fn quality_element_size(&self) -> c_int {
+        self.quality_element_size
+    }
+
+    pub fn set_int_notes(&mut self, notes:Option<&[c_int]>, element_size:c_int) -> DssResult<()> {
+        match notes {
+            Some(x) => {
+                if element_size < 1 || x.len() != self.values.len()*(element_size as usize) {
+                    Err(DssError::raise(format!("The length of the integer notes is not equal to TimeSeriesContainer capacity times element size {}",element_size)))?
+                }
+                self.inotes = Some(x.to_vec());
+                self.inote_element_size = element_size;
+            },
+            _ => {
+                self.inotes = None;
+                self.inote_element_size = 0;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn int_notes(&self) -> Option<&[c_int]> {
+        self.inotes.as_ref().map(|x| x.as_slice())
+    }
+
+    pub fn int_note_element_size(&self) -> c_int {
+        self.inote_element_size
+    }
+
+    pub fn set_char_notes(&mut self, notes:Option<&[&str]>) -> DssResult<()> {
+        match notes {
+            Some(x) => {
+                if x.len() != self.values.len() {
+                    Err(DssError::raise("The length of the character notes is not equal to TimeSeriesContainer capacity".to_string()))?
+                }
+                if x.iter().any(|note| note.contains('\x00')) {
+                    Err(DssError::raise("Character notes must not contain null characters".to_string()))?
+                }
+                self.cnotes = Some(x.iter().map(|note| note.to_string()).collect());
             },
-            _ => Err(DssError::raise("The length of the value is not equal to TimeSeriesContainer capacity".to_string()))?
+            _ => {self.cnotes = None;}
         }
+        Ok(())
     }
 
-    pub fn values(&self) -> &[f32] {
-        self.values.as_slice()
+    pub fn char_notes(&self) -> Option<&[String]> {
+        self.cnotes.as_ref().map(|x| x.as_slice())
     }
 
     pub fn set_times(&mut self, times:&[HecTime]) -> DssResult<()> {
@@ -550,9 +1135,9 @@ impl <'a> TimeSeriesContainer<'a> {
 
     pub fn times(&self,expand_regular:bool) -> Option<Vec<HecTime>>{
         if let TimeSeriesType::regular = self.ts_type {
-            if let Some(ref stime) = self.start_time {
+            if let Some(stime) = self.offset_start_time() {
                 let mut times = Vec::<HecTime>::new();
-                let mut start_time = stime.clone();
+                let mut start_time = stime;
                 if expand_regular {
                     if let Some(ref interval) = self.interval {
                         let interval_sec = interval.clone().value();
@@ -581,22 +1166,68 @@ impl <'a> TimeSeriesContainer<'a> {
         None       
     }
 
-    pub fn set_unit(&mut self, unit:&'a str) {
-        let unit = DataUnit::from_string(unit);
-        self.data_unit = unit;
+    fn offset_start_time(&self) -> Option<HecTime> {
+        // Shift the start time of regular data onto the time offset (e.g., 0600 for 6Hour data)
+        let stime = self.start_time?;
+        match self.interval {
+            Some(interval) if self.time_offset != 0 => {
+                let (mut julian,mut seconds) = stime.to_julian_seconds();
+                unsafe {
+                    ztsOffsetAdjustToOffset(self.time_offset,interval.value(),&mut julian,&mut seconds);
+                }
+                Some(HecTime::from_julian_seconds(julian,seconds,stime.granularity,stime.basedate_days))
+            },
+            _ => Some(stime)
+        }
+    }
+
+    pub fn set_time_zone(&mut self, time_zone:Option<&str>) {
+        self.time_zone = time_zone.map(|x| x.to_string());
+    }
+
+    pub fn time_zone(&self) -> Option<&str> {
+        self.time_zone.as_deref()
+    }
+
+    pub fn set_time_offset(&mut self, seconds:c_int) {
+        self.time_offset = seconds;
+    }
+
+    pub fn time_offset(&self) -> c_int {
+        self.time_offset
+    }
+
+    pub fn set_precision(&mut self, precision:Option<c_int>) {
+        // number of decimal places to display
+        self.precision = precision;
+    }
+
+    pub fn precision(&self) -> Option<c_int> {
+        self.precision
+    }
+
+    pub fn set_user_header(&mut self, header:Option<&[c_int]>) {
+        self.user_header = header.map(|x| x.to_vec());
+    }
+
+    pub fn user_header(&self) -> Option<&[c_int]> {
+        self.user_header.as_ref().map(|x| x.as_slice())
+    }
+
+    pub fn set_unit(&mut self, unit:&str) {
+        self.data_unit = unit.to_string();
     }
 
     pub fn unit(&self) -> DataUnit {
-        self.data_unit
+        DataUnit::from_string(&self.data_unit)
     }
 
-    pub fn set_type(&mut self, typ:&'a str) {
-        let dtype = DataType::from_string(typ);
-        self.data_type = dtype;
+    pub fn set_type(&mut self, typ:&str) {
+        self.data_type = typ.to_string();
     }
 
     pub fn dtype(&self) ->DataType {
-        self.data_type
+        DataType::from_string(&self.data_type)
     }
 
     fn set_interval(&mut self, interval:Option<HecTimeInterval>) {
@@ -625,20 +1256,387 @@ impl <'a> TimeSeriesContainer<'a> {
         };
     }
 
-    // Don't use
-    fn fix_pathname(&mut self) {
-        if self.ts_type == TimeSeriesType::regular {
-            if let Some(ref mut path) = self.pathname {
-                path.dpart = Some(self.interval.as_ref().unwrap().to_string());
+    unsafe fn from_struct(zts:*const zStructTimeSeries) -> DssResult<Self> {
+        // Create the output container
+        let interval = (*zts).timeIntervalSeconds;
+        let ts_type = TimeSeriesType::from_interval(interval);
+        let data_count = (*zts).numberValues as c_int;
+        let cstring = |ptr:*const c_char| if ptr.is_null() {
+                                                String::new()
+                                            } else {
+                                                CStr::from_ptr(ptr).to_string_lossy().into_owned()
+                                            };
+        let _path = DssPathname::from_string(&cstring((*zts).pathname));
+        let mut tsc = TimeSeriesContainer::new(ts_type,data_count);
+        
+        // pathname
+        tsc.set_pathname(_path);
+
+        // set meta data here (copied, zts is freed after the read)
+        tsc.set_type(&cstring((*zts).type_));
+        tsc.set_unit(&cstring((*zts).units));
+
+        // set data (only one of the float or double arrays is allocated)
+        let buf_ptr:*const f64 = (*zts).doubleValues;
+        if !buf_ptr.is_null() {
+            tsc.set_double_values(std::slice::from_raw_parts(buf_ptr, data_count as usize))?;
+        } else {
+            let buf_ptr:*const f32 = (*zts).floatValues;
+            if !buf_ptr.is_null() {
+                tsc.set_values(std::slice::from_raw_parts(buf_ptr, data_count as usize))?;
             }
         }
-    }
-}
 
-impl DssPathname {
-    pub fn new(a:Option<String>,b:Option<String>,c:Option<String>,d:Option<String>,e:Option<String>,f:Option<String>) -> Self {
-        DssPathname{apart:a,bpart:b,cpart:c,dpart:d,epart:e,fpart:f}
-    }
+        // set quality flags
+        let buf_ptr:*const c_int = (*zts).quality;
+        let quality_size = (*zts).qualityElementSize;
+        if !buf_ptr.is_null() && quality_size > 0 {
+            let buf = std::slice::from_raw_parts(buf_ptr, (data_count*quality_size) as usize);
+            tsc.set_quality_raw(Some(buf),quality_size)?;
+        }
+
+        // set time zone and offset
+        let time_zone = (*zts).timeZoneName;
+        if !time_zone.is_null() {
+            let time_zone = CStr::from_ptr(time_zone).to_string_lossy();
+            if !time_zone.trim().is_empty() {
+                tsc.set_time_zone(Some(time_zone.trim()));
+            }
+        }
+        tsc.set_time_offset((*zts).timeOffsetSeconds);
+
+        // set precision and user header
+        if (*zts).precision >= 0 {
+            tsc.set_precision(Some((*zts).precision));
+        }
+        let buf_ptr:*const c_int = (*zts).userHeader;
+        let header_count = (*zts).userHeaderNumber;
+        if !buf_ptr.is_null() && header_count > 0 {
+            tsc.set_user_header(Some(std::slice::from_raw_parts(buf_ptr, header_count as usize)));
+        }
+
+        // set notes
+        let buf_ptr:*const c_int = (*zts).inotes;
+        let inote_size = (*zts).inoteElementSize;
+        if !buf_ptr.is_null() && inote_size > 0 {
+            let buf = std::slice::from_raw_parts(buf_ptr, (data_count*inote_size) as usize);
+            tsc.set_int_notes(Some(buf),inote_size)?;
+        }
+        let buf_ptr = (*zts).cnotes;
+        let cnotes_len = (*zts).cnotesLengthTotal;
+        if !buf_ptr.is_null() && cnotes_len > 0 {
+            // cnotes holds one null terminated string per value
+            let buf = std::slice::from_raw_parts(buf_ptr as *const u8, cnotes_len as usize);
+            let mut notes:Vec<String> = buf.split(|x| *x == 0)
+                                           .take(data_count as usize)
+                                           .map(|x| String::from_utf8_lossy(x).into_owned())
+                                           .collect();
+            notes.resize(data_count as usize,String::new());
+            let notes:Vec<&str> = notes.iter().map(|x| x.as_str()).collect();
+            tsc.set_char_notes(Some(notes.as_slice()))?;
+        }
+
+        // set time values
+        let granularity = HecTimeGranularity::from_value((*zts).timeGranularitySeconds);
+        let mut basedate = 0 as c_int;
+        let mut times = Vec::<HecTime>::with_capacity(data_count as usize);
+        match ts_type {
+            TimeSeriesType::irregular => {
+                tsc.set_interval(None);
+                basedate = (*zts).julianBaseDate;
+                let buf_ptr = (*zts).times;
+                if !buf_ptr.is_null() {
+                    let buf = std::slice::from_raw_parts(buf_ptr, data_count as usize);
+                    for x in buf {
+                        times.push(HecTime{value:*x,granularity:granularity,basedate_days:basedate});
+                    }
+                    tsc.set_times(times.as_slice());
+                }
+            },
+            TimeSeriesType::regular => {
+                tsc.set_interval(Some(HecTimeInterval::second(interval)));
+                basedate = (*zts).startJulianDate;
+                let value = (((*zts).startTimeSeconds as f32)/(granularity.value() as f32)) as c_int;
+                let htime = HecTime{value:value,granularity:granularity,basedate_days:basedate};
+                times.push(htime);
+                tsc.set_times(times.as_slice());
+            }
+        }
+        Ok(tsc)
+    }
+
+    // Don't use
+    fn fix_pathname(&mut self) {
+        if self.ts_type == TimeSeriesType::regular {
+            if let Some(ref mut path) = self.pathname {
+                path.dpart = Some(self.interval.as_ref().unwrap().to_string());
+            }
+        }
+    }
+}
+
+impl TimeSeriesProfile {
+    pub fn new(ts_type:TimeSeriesType,num_times:c_int,num_depths:c_int) -> Self {
+        let depths = DataValues::float(vec![0f32;num_depths as usize]);
+        let values = DataValues::float(vec![0f32;(num_times*num_depths) as usize]);
+        TimeSeriesProfile::with_values(ts_type,num_times,depths,values)
+    }
+
+    pub fn new_double(ts_type:TimeSeriesType,num_times:c_int,num_depths:c_int) -> Self {
+        let depths = DataValues::double(vec![0f64;num_depths as usize]);
+        let values = DataValues::double(vec![0f64;(num_times*num_depths) as usize]);
+        TimeSeriesProfile::with_values(ts_type,num_times,depths,values)
+    }
+
+    fn with_values(ts_type:TimeSeriesType,num_times:c_int,depths:DataValues,values:DataValues) -> Self {
+        let times = match ts_type {
+            TimeSeriesType::irregular => Some(vec![HecTime::new(0,None,None);num_times as usize]),
+            TimeSeriesType::regular => None
+        };
+        TimeSeriesProfile{ts_type:ts_type,
+                          pathname:None,
+                          depths:depths,
+                          values:values,
+                          depth_unit:String::new(),
+                          value_unit:String::new(),
+                          data_type:String::new(),
+                          times:times,
+                          start_time:None,
+                          interval:None}
+    }
+
+    // For regular profiles the interval is taken from the E-part
+    pub fn set_pathname(&mut self, path:Option<DssPathname>) {
+        if self.ts_type == TimeSeriesType::regular {
+            if let Some(epart) = path.as_ref().and_then(|x| x.epart.as_ref()) {
+                self.interval = HecTimeInterval::from_string(epart);
+            }
+        }
+        self.pathname = path;
+    }
+
+    pub fn pathname(&self) -> Option<DssPathname> {
+        self.pathname.clone()
+    }
+
+    pub fn ts_type(&self) -> TimeSeriesType {
+        self.ts_type
+    }
+
+    pub fn shape(&self) -> (usize,usize) {
+        // (number of times, number of depths)
+        let depths = self.depths.len();
+        match depths {
+            0 => (0,0),
+            _ => (self.values.len()/depths,depths)
+        }
+    }
+
+    pub fn set_depths(&mut self, depths:&[f32]) -> DssResult<()> {
+        if depths.len() != self.depths.len() {
+            Err(DssError::raise("The length of the depths is not equal to TimeSeriesProfile depth count".to_string()))?
+        }
+        self.depths = DataValues::float(depths.to_vec());
+        Ok(())
+    }
+
+    pub fn set_double_depths(&mut self, depths:&[f64]) -> DssResult<()> {
+        if depths.len() != self.depths.len() {
+            Err(DssError::raise("The length of the depths is not equal to TimeSeriesProfile depth count".to_string()))?
+        }
+        self.depths = DataValues::double(depths.to_vec());
+        Ok(())
+    }
+
+    pub fn depths(&self) -> &DataValues {
+        &self.depths
+    }
+
+    pub fn set_values(&mut self, values:&[f32]) -> DssResult<()> {
+        if values.len() != self.values.len() {
+            Err(DssError::raise("The length of the value is not equal to TimeSeriesProfile capacity".to_string()))?
+        }
+        self.values = DataValues::float(values.to_vec());
+        Ok(())
+    }
+
+    pub fn set_double_values(&mut self, values:&[f64]) -> DssResult<()> {
+        if values.len() != self.values.len() {
+            Err(DssError::raise("The length of the value is not equal to TimeSeriesProfile capacity".to_string()))?
+        }
+        self.values = DataValues::double(values.to_vec());
+        Ok(())
+    }
+
+    pub fn values(&self) -> &DataValues {
+        &self.values
+    }
+
+    pub fn is_double(&self) -> bool {
+        self.values.is_double()
+    }
+
+    pub fn profile(&self, index:usize) -> Option<Vec<f64>> {
+        let (rows,cols) = self.shape();
+        if index >= rows {
+            return None
+        }
+        Some(self.values.to_double()[index*cols..(index+1)*cols].to_vec())
+    }
+
+    pub fn set_times(&mut self, times:&[HecTime]) -> DssResult<()> {
+        if times.is_empty() {
+            Err(DssError::raise("No times given for TimeSeriesProfile".to_string()))?
+        }
+        match self.ts_type {
+            TimeSeriesType::regular => {self.start_time = Some(times[0]);},
+            TimeSeriesType::irregular => {
+                if times.len() != self.shape().0 {
+                    Err(DssError::raise("The length of the times is not equal to TimeSeriesProfile time count".to_string()))?
+                }
+                self.times = Some(times.to_vec());
+            }
+        }
+        Ok(())
+    }
+
+    pub fn times(&self,expand_regular:bool) -> Option<Vec<HecTime>> {
+        match self.ts_type {
+            TimeSeriesType::irregular => self.times.clone(),
+            TimeSeriesType::regular => {
+                let mut time = self.start_time?;
+                let count = if expand_regular {self.shape().0} else {1};
+                let interval_sec = self.interval?.value();
+                let mut times = Vec::<HecTime>::with_capacity(count);
+                for i in 0..count {
+                    if i > 0 {
+                        time.add_seconds(interval_sec);
+                    }
+                    times.push(time);
+                }
+                Some(times)
+            }
+        }
+    }
+
+    pub fn set_interval(&mut self, interval:HecTimeInterval) {
+        self.interval = Some(interval);
+    }
+
+    pub fn interval(&self) -> Option<HecTimeInterval> {
+        self.interval
+    }
+
+    pub fn set_depth_unit(&mut self, unit:&str) {
+        self.depth_unit = unit.to_string();
+    }
+
+    pub fn depth_unit(&self) -> DataUnit {
+        DataUnit::from_string(&self.depth_unit)
+    }
+
+    pub fn set_value_unit(&mut self, unit:&str) {
+        self.value_unit = unit.to_string();
+    }
+
+    pub fn value_unit(&self) -> DataUnit {
+        DataUnit::from_string(&self.value_unit)
+    }
+
+    pub fn set_type(&mut self, typ:&str) {
+        self.data_type = typ.to_string();
+    }
+
+    pub fn dtype(&self) -> DataType {
+        DataType::from_string(&self.data_type)
+    }
+
+    unsafe fn from_struct(zts:*const zStructTimeSeries) -> DssResult<Self> {
+        let interval = (*zts).timeIntervalSeconds;
+        let ts_type = TimeSeriesType::from_interval(interval);
+        let num_times = (*zts).numberValues as c_int;
+        let num_depths = (*zts).profileDepthsNumber as c_int;
+        if num_depths < 1 {
+            Err(DssError::raise("Record is not a time-series profile".to_string()))?
+        }
+        let count = (num_times*num_depths) as usize;
+        let mut profile = TimeSeriesProfile::new(ts_type,num_times,num_depths);
+        let cstring = |ptr:*const c_char| if ptr.is_null() {
+                                                String::new()
+                                            } else {
+                                                CStr::from_ptr(ptr).to_string_lossy().into_owned()
+                                            };
+        profile.set_pathname(DssPathname::from_string(&cstring((*zts).pathname)));
+        profile.set_type(&cstring((*zts).type_));
+        profile.set_depth_unit(&cstring((*zts).unitsProfileDepths));
+        profile.set_value_unit(&cstring((*zts).unitsProfileValues));
+
+        // set data (only one of the float or double arrays is allocated)
+        if !(*zts).doubleProfileValues.is_null() {
+            profile.set_double_depths(std::slice::from_raw_parts((*zts).doubleProfileDepths, num_depths as usize))?;
+            profile.set_double_values(std::slice::from_raw_parts((*zts).doubleProfileValues, count))?;
+        } else if !(*zts).floatProfileValues.is_null() {
+            profile.set_depths(std::slice::from_raw_parts((*zts).floatProfileDepths, num_depths as usize))?;
+            profile.set_values(std::slice::from_raw_parts((*zts).floatProfileValues, count))?;
+        }
+
+        // set time values
+        let granularity = HecTimeGranularity::from_value((*zts).timeGranularitySeconds);
+        match ts_type {
+            TimeSeriesType::irregular => {
+                let basedate = (*zts).julianBaseDate;
+                if !(*zts).times.is_null() {
+                    let times:Vec<HecTime> = std::slice::from_raw_parts((*zts).times, num_times as usize).iter()
+                                                 .map(|x| HecTime{value:*x,granularity:granularity,basedate_days:basedate})
+                                                 .collect();
+                    profile.set_times(times.as_slice())?;
+                }
+            },
+            TimeSeriesType::regular => {
+                profile.interval = Some(HecTimeInterval::second(interval));
+                let value = (*zts).startTimeSeconds/granularity.value();
+                profile.set_times(&[HecTime{value:value,granularity:granularity,basedate_days:(*zts).startJulianDate}])?;
+            }
+        }
+        Ok(profile)
+    }
+}
+
+impl TimeSeriesSlice {
+    pub fn new(start_time:Option<HecTime>,end_time:Option<HecTime>) -> Self {
+        TimeSeriesSlice{start_time:start_time,end_time:end_time,trim:false}
+    }
+
+    pub fn set_trim(&mut self,trim:bool) {
+        self.trim = trim;
+    }
+}
+
+impl TimeSeriesOptions {
+    pub fn new() -> Self {
+        TimeSeriesOptions{slice:None,trim_start:None,trim_end:None}
+    }
+
+    pub fn set_slice(&mut self,slice:TimeSeriesSlice) {
+        self.slice = Some(slice);
+    }
+
+    pub fn set_trim(&mut self,trim_start:bool,trim_end:bool) {
+        self.trim_start = Some(trim_start);
+        self.trim_end = Some(trim_end);
+    }
+
+    fn trim_flags(&self) -> (bool,bool) {
+        // trim_start/trim_end override the trim flag of the slice
+        let trim = self.slice.as_ref().map_or(false,|x| x.trim);
+        (self.trim_start.unwrap_or(trim),self.trim_end.unwrap_or(trim))
+    }
+}
+
+impl DssPathname {
+    pub fn new(a:Option<String>,b:Option<String>,c:Option<String>,d:Option<String>,e:Option<String>,f:Option<String>) -> Self {
+        DssPathname{apart:a,bpart:b,cpart:c,dpart:d,epart:e,fpart:f}
+    }
 
     pub fn to_string(&self) -> String {
         let empty = "".to_string();
@@ -669,6 +1667,66 @@ impl DssPathname {
                 None}
         }
     }
+
+    fn parts(&self) -> [&str;6] {
+        [self.apart.as_deref().unwrap_or(""),
+         self.bpart.as_deref().unwrap_or(""),
+         self.cpart.as_deref().unwrap_or(""),
+         self.dpart.as_deref().unwrap_or(""),
+         self.epart.as_deref().unwrap_or(""),
+         self.fpart.as_deref().unwrap_or("")]
+    }
+}
+
+impl PathnamePattern {
+    // None unless the pattern is /A/B/C/D/E/F/
+    pub fn from_string(pattern:&str) -> Option<Self> {
+        if !pattern.starts_with("/") || !pattern.ends_with("/") {
+            return None
+        }
+        let parts = pattern.split("/").map(|x| x.trim().to_uppercase()).collect::<Vec<_>>();
+        match parts.len() {
+            8 => Some(PathnamePattern{parts:parts[1..7].to_vec()}),
+            _ => None
+        }
+    }
+
+    pub fn to_string(&self) -> String {
+        format!("/{}/",self.parts.join("/"))
+    }
+
+    pub fn matches(&self,pathname:&DssPathname) -> bool {
+        self.parts.iter().zip(pathname.parts().iter())
+            .all(|(pattern,part)| wildcard_match(pattern,&part.to_uppercase()))
+    }
+
+    fn is_native(&self) -> bool {
+        // heclib only handles * at the start or end of a part
+        self.parts.iter().all(|x| {
+            let inner = x.trim_start_matches('*').trim_end_matches('*');
+            !inner.contains('*')
+        })
+    }
+}
+
+fn wildcard_match(pattern:&str,text:&str) -> bool {
+    let pieces = pattern.split('*').collect::<Vec<_>>();
+    if pieces.len() == 1 {
+        return pattern == text
+    }
+    let first = pieces[0];
+    let last = pieces[pieces.len()-1];
+    if text.len() < first.len() + last.len() || !text.starts_with(first) || !text.ends_with(last) {
+        return false
+    }
+    let mut rest = &text[first.len()..text.len()-last.len()];
+    for piece in &pieces[1..pieces.len()-1] {
+        match rest.find(piece) {
+            Some(i) => rest = &rest[i+piece.len()..],
+            None => return false
+        }
+    }
+    true
 }
 
 impl PairedDataSlice {
@@ -689,32 +1747,119 @@ impl PairedDataSlice {
         self.col_start = start;
         self.col_end = Some(end);
     }
+
+    // (row_start,row_end,col_start,col_end) checked against the record shape
+    fn bounds(&self,pd_rows:c_int,pd_cols:c_int) -> DssResult<(c_int,c_int,c_int,c_int)> {
+        let row_start = std::cmp::max(self.row_start,1);
+        let col_start = std::cmp::max(self.col_start,1);
+        let row_end = self.row_end.unwrap_or(pd_rows);
+        let col_end = self.col_end.unwrap_or(pd_cols);
+        if row_start > row_end || row_end > pd_rows {
+            Err(DssError::raise(format!("Paired Data rows {} - {} not in the range 1 - {}",row_start,row_end,pd_rows)))?;
+        }
+        if col_start > col_end || col_end > pd_cols {
+            Err(DssError::raise(format!("Paired Data columns {} - {} not in the range 1 - {}",col_start,col_end,pd_cols)))?;
+        }
+        Ok((row_start,row_end,col_start,col_end))
+    }
+}
+
+impl LookupOptions {
+    pub fn new() -> Self {
+        LookupOptions{method:InterpolationMethod::linear,out_of_range:OutOfRange::error}
+    }
+
+    pub fn set_method(&mut self,method:InterpolationMethod) {
+        self.method = method;
+    }
+
+    pub fn set_out_of_range(&mut self,out_of_range:OutOfRange) {
+        self.out_of_range = out_of_range;
+    }
+}
+
+// Interpolates y at x, xs must be strictly increasing or decreasing
+fn interpolate(xs:&[f64],ys:&[f64],x:f64,options:&LookupOptions) -> DssResult<f64> {
+    let n = xs.len();
+    if n == 0 || n != ys.len() {
+        Err(DssError::raise("Paired Data curve has no values to interpolate".to_string()))?;
+    }
+    if n == 1 {
+        return match (xs[0] == x,options.out_of_range) {
+            (true,_) | (false,OutOfRange::clamp) => Ok(ys[0]),
+            _ => Err(DssError::raise(format!("Value {} outside of the single point curve at {}",x,xs[0])))
+        }
+    }
+    let increasing = xs[1] > xs[0];
+    if xs.windows(2).any(|w| if increasing {w[1] <= w[0]} else {w[1] >= w[0]}) {
+        Err(DssError::raise("Paired Data curve is not strictly monotonic".to_string()))?;
+    }
+    let (low,high) = if increasing {(xs[0],xs[n-1])} else {(xs[n-1],xs[0])};
+    let i = if x < low || x > high {
+        match options.out_of_range {
+            OutOfRange::error => Err(DssError::raise(format!("Value {} outside of the curve range {} - {}",x,low,high)))?,
+            OutOfRange::clamp => return Ok(if (x < low) == increasing {ys[0]} else {ys[n-1]}),
+            OutOfRange::extrapolate => if (x < low) == increasing {0} else {n-2}
+        }
+    } else {
+        // segment i..i+1 containing x
+        let pos = xs.iter().position(|v| if increasing {*v >= x} else {*v <= x}).unwrap_or(n-1);
+        if pos == 0 {0} else {pos-1}
+    };
+    let (x0,x1,y0,y1) = (xs[i],xs[i+1],ys[i],ys[i+1]);
+    match options.method {
+        InterpolationMethod::linear => Ok(y0 + (x - x0)*(y1 - y0)/(x1 - x0)),
+        InterpolationMethod::log_log => {
+            if x <= 0.0 || x0 <= 0.0 || x1 <= 0.0 || y0 <= 0.0 || y1 <= 0.0 {
+                Err(DssError::raise(format!("Log-log interpolation needs positive values around {}",x)))?;
+            }
+            let (lx,lx0,lx1,ly0,ly1) = (x.ln(),x0.ln(),x1.ln(),y0.ln(),y1.ln());
+            Ok((ly0 + (lx - lx0)*(ly1 - ly0)/(lx1 - lx0)).exp())
+        }
+    }
 }
 
 impl PairedDataOptions {
     pub fn new() -> Self {
-        PairedDataOptions{slice:PairedDataSlice::new()}
+        PairedDataOptions{slice:PairedDataSlice::new(),as_double:None}
+    }
+
+    pub fn set_slice(&mut self,slice:PairedDataSlice) {
+        self.slice = slice;
+    }
+
+    // None reads the values as stored
+    pub fn set_as_double(&mut self,as_double:Option<bool>) {
+        self.as_double = as_double;
     }
 }
 
-impl <'a> PairedDataTable<'a> {
+impl PairedDataTable {
     pub fn new(row_count:c_int,col_count:c_int) -> Self {
-        let mut pathname:Option<DssPathname>= None;
-        let mut columns = vec![vec![0f32;row_count as usize];col_count as usize];
-        //let columns = vec![0f32,(row_count as usize),(col_count as usize)];
-        let mut index = vec![0f32;row_count as usize];
-        //let mut unit = DataUnit::undefined("");
-        //let mut dtype = DataType::undefined("");
+        let index = DataValues::float(vec![0f32;row_count as usize]);
+        let columns = vec![DataValues::float(vec![0f32;row_count as usize]);col_count as usize];
+        PairedDataTable::with_values(row_count,col_count,index,columns)
+    }
+
+    pub fn new_double(row_count:c_int,col_count:c_int) -> Self {
+        let index = DataValues::double(vec![0f64;row_count as usize]);
+        let columns = vec![DataValues::double(vec![0f64;row_count as usize]);col_count as usize];
+        PairedDataTable::with_values(row_count,col_count,index,columns)
+    }
 
-        PairedDataTable{pathname:pathname,
+    fn with_values(row_count:c_int,col_count:c_int,index:DataValues,columns:Vec<DataValues>) -> Self {
+        PairedDataTable{pathname:None,
                         shape:(row_count,col_count),
                         headers:None,
                         index:index,
                         columns:columns,
-                        index_unit:DataUnit::undefined(""),
-                        index_type:DataType::undefined(""),
-                        column_unit:DataUnit::undefined(""),
-                        column_type:DataType::undefined("")
+                        index_unit:String::new(),
+                        index_type:String::new(),
+                        column_unit:String::new(),
+                        column_type:String::new(),
+                        index_is_xaxis:true,
+                        precision:None,
+                        time_zone:None
                     }
     }
 
@@ -722,104 +1867,934 @@ impl <'a> PairedDataTable<'a> {
         self.pathname = Some(path);
     }
 
+    pub fn pathname(&self) -> Option<&DssPathname> {
+        self.pathname.as_ref()
+    }
+
+    // (rows,columns)
+    pub fn shape(&self) -> (c_int,c_int) {
+        self.shape
+    }
+
+    pub fn index(&self) -> &DataValues {
+        &self.index
+    }
+
+    pub fn columns(&self) -> &[DataValues] {
+        &self.columns
+    }
+
+    pub fn is_double(&self) -> bool {
+        self.index.is_double()
+    }
+
+    pub fn headers(&self) -> Option<&[String]> {
+        self.headers.as_deref()
+    }
+
+    pub fn column(&self,label:&str) -> Option<&DataValues> {
+        self.label_position(label).ok().map(|i| &self.columns[i])
+    }
+
+    // Values can be edited in place, their length and precision are checked again on write
+    pub fn column_mut(&mut self,label:&str) -> Option<&mut DataValues> {
+        match self.label_position(label) {
+            Ok(i) => Some(&mut self.columns[i]),
+            Err(_) => None
+        }
+    }
+
+    // Appends a labelled column, the table must be labelled unless it has no columns yet
+    pub fn add_column(&mut self,label:&str,values:DataValues) -> DssResult<()> {
+        if values.len() != self.shape.0 as usize {
+            Err(DssError::raise("The length of the value is not equal to PairedDataTable index capacity".to_string()))?
+        }
+        if values.is_double() != self.index.is_double() {
+            Err(DssError::raise("Index and columns of PairedDataTable must have the same precision".to_string()))?;
+        }
+        if self.headers.is_none() && !self.columns.is_empty() {
+            Err(DssError::raise("Cannot add labelled column to PairedDataTable without headers".to_string()))?;
+        }
+        if self.label_position(label).is_ok() {
+            Err(DssError::raise(format!("Paired Data column {:?} already exists",label)))?;
+        }
+        self.headers.get_or_insert_with(Vec::new).push(label.to_string());
+        self.columns.push(values);
+        self.shape.1 += 1;
+        Ok(())
+    }
+
+    pub fn remove_column(&mut self,label:&str) -> DssResult<DataValues> {
+        let i = self.label_position(label)?;
+        if let Some(ref mut headers) = self.headers {
+            headers.remove(i);
+        }
+        self.shape.1 -= 1;
+        Ok(self.columns.remove(i))
+    }
+
+    // (label,values) of each column, labels are empty for a table without headers
+    pub fn iter_columns(&self) -> impl Iterator<Item=(&str,&DataValues)> {
+        let headers = self.headers.as_deref().unwrap_or(&[]);
+        self.columns.iter().enumerate()
+            .map(move |(i,x)| (headers.get(i).map_or("",|h| h.as_str()),x))
+    }
+
     pub fn set_index(&mut self, values:&[f32]) -> DssResult<()> {
-        let (rows,cols) = self.shape;
-        let length = (rows*cols) as usize;
-        match values.len() {
-            length => {
-                self.index.clone_from_slice(values);
-                Ok(())
-            },
-            _ => {Err(DssError::raise("The length of the value is not equal to PairedDataTable index capacity".to_string()))?}
+        if values.len() != self.shape.0 as usize {
+            Err(DssError::raise("The length of the value is not equal to PairedDataTable index capacity".to_string()))?
+        }
+        self.index = DataValues::float(values.to_vec());
+        Ok(())
+    }
+
+    pub fn set_double_index(&mut self, values:&[f64]) -> DssResult<()> {
+        if values.len() != self.shape.0 as usize {
+            Err(DssError::raise("The length of the value is not equal to PairedDataTable index capacity".to_string()))?
         }
+        self.index = DataValues::double(values.to_vec());
+        Ok(())
     }
 
+    // values of all columns, one column after another
     pub fn set_columns(&mut self, values:&[f32]) -> DssResult<()> {
         let (rows,cols) = self.shape;
-        let length = (rows*cols) as usize;
-        match values.len() {
-            length => {
-                let mut count = 0;
-                for col in 0..(cols as usize) {
-                    for row in 0..(rows as usize) {
-                        self.columns[col][row] = values[count];
-                        count += 1;
-                    }
-                }
-                Ok(())
-            },
-            _ => Err(DssError::raise("The length of the value is not equal to PairedDataTable column capacity".to_string()))?
+        if values.len() != (rows*cols) as usize {
+            Err(DssError::raise("The length of the value is not equal to PairedDataTable column capacity".to_string()))?
+        }
+        self.columns = values.chunks(rows as usize).map(|x| DataValues::float(x.to_vec())).collect();
+        Ok(())
+    }
+
+    pub fn set_double_columns(&mut self, values:&[f64]) -> DssResult<()> {
+        let (rows,cols) = self.shape;
+        if values.len() != (rows*cols) as usize {
+            Err(DssError::raise("The length of the value is not equal to PairedDataTable column capacity".to_string()))?
         }
+        self.columns = values.chunks(rows as usize).map(|x| DataValues::double(x.to_vec())).collect();
+        Ok(())
     }
 
-    pub fn set_headers(&mut self,headers:Option<Vec<&'a str>>) -> DssResult<()>{
+    pub fn set_headers(&mut self,headers:Option<&[&str]>) -> DssResult<()>{
         match headers {
-            Some(ref x) => {
-                if (*x).len() != (self.shape.1 as usize) {
+            Some(x) => {
+                if x.len() != (self.shape.1 as usize) {
                     println!("PD Headers = {:?}",headers);
                     Err(DssError::raise(format!("Invalid number ({}) of column header provided",x.len())))?
                 };
             },
             _ => {}
         }
-        self.headers = headers;
+        self.headers = headers.map(|x| x.iter().map(|h| h.to_string()).collect());
         Ok(())
     }
 
-    pub fn set_index_unit(&mut self,unit:&'a str){
-        let unit = DataUnit::from_string(unit);
-        self.index_unit = unit;
+    pub fn set_index_unit(&mut self,unit:&str){
+        self.index_unit = unit.to_string();
     }
 
     pub fn index_unit(&self) -> DataUnit {
-        self.index_unit
+        DataUnit::from_string(&self.index_unit)
     }
 
-    pub fn set_index_type(&mut self,typ:&'a str) {
-        let typ = DataType::from_string(typ);
-        self.index_type = typ;  
+    pub fn set_index_type(&mut self,typ:&str) {
+        self.index_type = typ.to_string();
     }
 
     pub fn index_type(&self) -> DataType {
-        self.index_type
+        DataType::from_string(&self.index_type)
     }
 
-    pub fn set_column_unit(&mut self,unit:&'a str) {
-        let unit = DataUnit::from_string(unit);
-        self.column_unit = unit;
-
+    pub fn set_column_unit(&mut self,unit:&str) {
+        self.column_unit = unit.to_string();
     }
 
     pub fn column_unit(&self) -> DataUnit {
-        self.index_unit
+        DataUnit::from_string(&self.column_unit)
     }
 
-    pub fn set_column_type(&mut self,typ:&'a str) {
-        let typ = DataType::from_string(typ);
-        self.index_type = typ;
+    pub fn set_column_type(&mut self,typ:&str) {
+        self.column_type = typ.to_string();
     }
 
     pub fn column_type(&self) -> DataType {
-        self.index_type
+        DataType::from_string(&self.column_type)
     }
 
-}
+    pub fn set_index_is_xaxis(&mut self,index_is_xaxis:bool) {
+        self.index_is_xaxis = index_is_xaxis;
+    }
 
-impl HecDss {
-    #[cfg_attr(feature="threadsafe",nonparallel(MUTX))]
-    pub fn new(dss_file:String) -> Result<Self,Box<dyn Error>> {//DssResult<Self> {
-        let mut ifltab = [0i64;500];
-        let path = CString::new(dss_file.clone())?;
-        let mut err = DssError::new();
-        unsafe {
-            hec_dss_zopen(ifltab.as_mut_slice().as_mut_ptr(),path.as_ptr());
-            err = err.update();
+    pub fn index_is_xaxis(&self) -> bool {
+        self.index_is_xaxis
+    }
+
+    // decimal places of the index and of the column values
+    pub fn set_precision(&mut self,precision:Option<(c_int,c_int)>) {
+        self.precision = precision;
+    }
+
+    pub fn precision(&self) -> Option<(c_int,c_int)> {
+        self.precision
+    }
+
+    pub fn set_time_zone(&mut self,time_zone:Option<&str>) {
+        self.time_zone = time_zone.map(|x| x.to_string());
+    }
+
+    pub fn time_zone(&self) -> Option<&str> {
+        self.time_zone.as_deref()
+    }
+
+    // null terminated labels as expected by zStructPairedData
+    fn labels_buffer(&self) -> DssResult<Option<Vec<u8>>> {
+        match self.headers {
+            Some(ref headers) => {
+                let mut buffer = Vec::<u8>::new();
+                for header in headers {
+                    if header.contains('\x00') {
+                        Err(DssError::raise(format!("Paired Data header {:?} contains null character",header)))?;
+                    }
+                    buffer.extend_from_slice(header.as_bytes());
+                    buffer.push(0);
+                }
+                Ok(Some(buffer))
+            },
+            None => Ok(None)
+        }
+    }
+
+    // Value of the column with label at index value x
+    pub fn lookup(&self,label:&str,x:f64,options:Option<LookupOptions>) -> DssResult<f64> {
+        let col = self.label_position(label)?;
+        interpolate(&self.index.to_double(),&self.columns[col].to_double(),x,&options.unwrap_or(LookupOptions::new()))
+    }
+
+    // Index value at which the column with label reaches y, the column must be monotonic
+    pub fn inverse_lookup(&self,label:&str,y:f64,options:Option<LookupOptions>) -> DssResult<f64> {
+        let col = self.label_position(label)?;
+        interpolate(&self.columns[col].to_double(),&self.index.to_double(),y,&options.unwrap_or(LookupOptions::new()))
+    }
+
+    fn label_position(&self,label:&str) -> DssResult<usize> {
+        self.headers.as_ref()
+            .and_then(|x| x.iter().position(|h| h == label))
+            .ok_or_else(|| DssError::raise(format!("Paired Data column {:?} not found",label)))
+    }
+
+    // all columns, one after another
+    fn values_concat(&self) -> DataValues {
+        if self.is_double() {
+            DataValues::double(self.columns.iter().flat_map(|x| x.to_double()).collect())
+        } else {
+            DataValues::float(self.columns.iter().flat_map(|x| x.as_float().unwrap_or(&[]).to_vec()).collect())
+        }
+    }
+
+    // index and columns must share the precision of the record
+    fn check_precision(&self) -> DssResult<()> {
+        let is_double = self.index.is_double();
+        if self.columns.iter().any(|x| x.is_double() != is_double) {
+            Err(DssError::raise("Index and columns of PairedDataTable must have the same precision".to_string()))?;
+        }
+        // columns may have been edited through column_mut
+        let rows = self.shape.0 as usize;
+        if self.index.len() != rows || self.columns.iter().any(|x| x.len() != rows) {
+            Err(DssError::raise("The length of the columns is not equal to PairedDataTable index capacity".to_string()))?;
+        }
+        Ok(())
+    }
+}
+
+impl RecordType {
+    pub fn from_value(value:c_int) -> Self {
+        match value {
+            100 => RecordType::rts,
+            101 => RecordType::rts_pattern,
+            102 => RecordType::rts_profile,
+            105 => RecordType::rtd,
+            106 => RecordType::rtd_pattern,
+            107 => RecordType::rtd_profile,
+            110 => RecordType::its,
+            111 => RecordType::its_pattern,
+            112 => RecordType::its_profile,
+            115 => RecordType::itd,
+            116 => RecordType::itd_pattern,
+            117 => RecordType::itd_profile,
+            200 => RecordType::pd,
+            205 => RecordType::pdd,
+            300 => RecordType::text,
+            310 => RecordType::text_table,
+            450 => RecordType::tin,
+            400..=499 => RecordType::grid(value),
+            20 => RecordType::location,
+            90..=93 => RecordType::array,
+            600 => RecordType::file,
+            610 => RecordType::image,
+            _ => RecordType::undefined(value)
+        }
+    }
+
+    pub fn value(&self) -> c_int {
+        match *self {
+            RecordType::rts => 100,
+            RecordType::rts_pattern => 101,
+            RecordType::rts_profile => 102,
+            RecordType::rtd => 105,
+            RecordType::rtd_pattern => 106,
+            RecordType::rtd_profile => 107,
+            RecordType::its => 110,
+            RecordType::its_pattern => 111,
+            RecordType::its_profile => 112,
+            RecordType::itd => 115,
+            RecordType::itd_pattern => 116,
+            RecordType::itd_profile => 117,
+            RecordType::pd => 200,
+            RecordType::pdd => 205,
+            RecordType::text => 300,
+            RecordType::text_table => 310,
+            RecordType::grid(x) => x,
+            RecordType::tin => 450,
+            RecordType::location => 20,
+            RecordType::array => 90,
+            RecordType::file => 600,
+            RecordType::image => 610,
+            RecordType::undefined(x) => x,
+        }
+    }
+
+    pub fn is_time_series(&self) -> bool {
+        (100..200).contains(&self.value())
+    }
+
+    pub fn is_paired_data(&self) -> bool {
+        (200..300).contains(&self.value())
+    }
+}
+
+impl CatalogOptions {
+    pub fn new() -> Self {
+        CatalogOptions{filter:None,sorted:true,include_dates:false,include_crc:false}
+    }
+
+    pub fn set_filter(&mut self,filter:PathnamePattern) {
+        self.filter = Some(filter);
+    }
+
+    pub fn set_sorted(&mut self,sorted:bool) {
+        self.sorted = sorted;
+    }
+
+    pub fn set_include_dates(&mut self,include_dates:bool) {
+        self.include_dates = include_dates;
+    }
+
+    pub fn set_include_crc(&mut self,include_crc:bool) {
+        self.include_crc = include_crc;
+    }
+}
+
+impl CatalogEntry {
+    pub fn pathname(&self) -> &DssPathname {
+        &self.pathname
+    }
+
+    pub fn record_type(&self) -> RecordType {
+        self.record_type
+    }
+
+    pub fn dates(&self) -> Option<(HecTime,HecTime)> {
+        self.dates.map(|(start,end)| (HecTime::new(start,Some(HecTimeGranularity::day),None),
+                                      HecTime::new(end,Some(HecTimeGranularity::day),None)))
+    }
+
+    pub fn last_write_time(&self) -> Option<i64> {
+        self.last_write_time
+    }
+
+    pub fn crc(&self) -> Option<c_uint> {
+        self.crc
+    }
+}
+
+impl CondensedPathname {
+    // pathname with the D part replaced by the range of block dates
+    pub fn to_string(&self) -> String {
+        let mut path = self.pathname.clone();
+        path.dpart = if self.block_count > 1 {
+            Some(format!("{} - {}",self.first.dpart.as_deref().unwrap_or(""),self.last.dpart.as_deref().unwrap_or("")))
+        } else {
+            self.first.dpart.clone()
+        };
+        path.to_string()
+    }
+
+    pub fn pathname(&self) -> &DssPathname {
+        &self.pathname
+    }
+
+    pub fn first_pathname(&self) -> &DssPathname {
+        &self.first
+    }
+
+    pub fn last_pathname(&self) -> &DssPathname {
+        &self.last
+    }
+
+    pub fn record_type(&self) -> RecordType {
+        self.record_type
+    }
+
+    pub fn block_count(&self) -> usize {
+        self.block_count
+    }
+
+    pub fn time_range(&self) -> Option<(HecTime,HecTime)> {
+        self.time_range
+    }
+}
+
+impl ChangeTracker {
+    pub fn len(&self) -> usize {
+        self.pathnames.len()
+    }
+
+    pub fn use_crc(&self) -> bool {
+        self.use_crc
+    }
+
+    pub fn filename(&self) -> &str {
+        &self.filename
+    }
+
+    fn native_filter(filter:&Option<PathnamePattern>) -> DssResult<Option<CString>> {
+        match filter {
+            Some(x) if x.is_native() => Ok(Some(CString::new(x.to_string()).map_err(|e| DssError::raise(e.to_string()))?)),
+            _ => Ok(None)
+        }
+    }
+}
+
+impl RecordChange {
+    pub fn pathname(&self) -> &DssPathname {
+        &self.pathname
+    }
+
+    pub fn kind(&self) -> ChangeKind {
+        self.kind
+    }
+}
+
+impl RecordInfo {
+    pub fn pathname(&self) -> &DssPathname {
+        &self.pathname
+    }
+
+    pub fn record_type(&self) -> RecordType {
+        self.record_type
+    }
+
+    pub fn version(&self) -> c_int {
+        self.version
+    }
+
+    pub fn number_values(&self) -> c_int {
+        self.number_values
+    }
+
+    pub fn logical_number_values(&self) -> c_int {
+        self.logical_number_values
+    }
+
+    pub fn allocated_size(&self) -> c_int {
+        self.allocated_size
+    }
+
+    pub fn last_write_time(&self) -> i64 {
+        self.last_write_time
+    }
+
+    pub fn creation_time(&self) -> i64 {
+        self.creation_time
+    }
+
+    pub fn program(&self) -> &str {
+        &self.program
+    }
+
+    pub fn ts_size(&self) -> Option<&TimeSeriesRecordSize> {
+        self.ts_size.as_ref()
+    }
+
+    pub fn pd_size(&self) -> Option<&PairedDataRecordSize> {
+        self.pd_size.as_ref()
+    }
+}
+
+impl TimeSeriesRecordSize {
+    pub fn precision(&self) -> c_int {
+        self.precision
+    }
+
+    pub fn time_offset(&self) -> c_int {
+        self.time_offset
+    }
+
+    pub fn profile_depths(&self) -> c_int {
+        self.profile_depths
+    }
+
+    pub fn block_position(&self) -> (c_int,c_int) {
+        (self.block_start,self.block_end)
+    }
+
+    pub fn value_size(&self) -> c_int {
+        self.value_size
+    }
+
+    pub fn value_element_size(&self) -> c_int {
+        self.value_element_size
+    }
+
+    pub fn quality_element_size(&self) -> c_int {
+        self.quality_element_size
+    }
+
+    pub fn inote_element_size(&self) -> c_int {
+        self.inote_element_size
+    }
+
+    pub fn cnotes_length(&self) -> c_int {
+        self.cnotes_length
+    }
+}
+
+impl PairedDataRecordSize {
+    pub fn number_curves(&self) -> c_int {
+        self.number_curves
+    }
+
+    pub fn number_ordinates(&self) -> c_int {
+        self.number_ordinates
+    }
+
+    pub fn value_size(&self) -> c_int {
+        self.value_size
+    }
+
+    pub fn independent_is_xaxis(&self) -> bool {
+        self.independent_is_xaxis
+    }
+
+    pub fn labels_length(&self) -> c_int {
+        self.labels_length
+    }
+
+    pub fn precision(&self) -> c_int {
+        self.precision
+    }
+}
+
+impl IntegrityCheck {
+    pub fn to_string(&self) -> String {
+        match self {
+            IntegrityCheck::file => "file".to_string(),
+            IntegrityCheck::hash_table => "hash_table".to_string(),
+            IntegrityCheck::links => "links".to_string(),
+            IntegrityCheck::pathnames => "pathnames".to_string(),
+        }
+    }
+}
+
+impl IntegrityIssue {
+    pub fn check(&self) -> IntegrityCheck {
+        self.check
+    }
+
+    pub fn kind(&self) -> &DssErrorKind {
+        &self.kind
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    // Pathname of the last record the check failed on
+    pub fn last_pathname(&self) -> Option<&str> {
+        self.last_pathname.as_deref()
+    }
+
+    // File address of the last failure reported by the check
+    pub fn last_address(&self) -> Option<i64> {
+        self.last_address
+    }
+}
+
+impl IntegrityReport {
+    pub fn is_ok(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    pub fn checks(&self) -> &[(IntegrityCheck,c_int)] {
+        &self.checks
+    }
+
+    pub fn issues(&self) -> &[IntegrityIssue] {
+        &self.issues
+    }
+}
+
+// Time-series convert without a D-part, which read_ts reads over the whole record set
+impl From<CondensedPathname> for DssPathname {
+    fn from(path:CondensedPathname) -> Self {
+        path.pathname
+    }
+}
+
+impl From<&CondensedPathname> for DssPathname {
+    fn from(path:&CondensedPathname) -> Self {
+        path.pathname.clone()
+    }
+}
+
+impl HecDss {
+    #[cfg_attr(feature="threadsafe",nonparallel(MUTX))]
+    pub fn new(dss_file:String) -> Result<Self,Box<dyn Error>> {//DssResult<Self> {
+        let mut ifltab = [0i64;500];
+        let path = CString::new(dss_file.clone())?;
+        let mut err = DssError::new();
+        unsafe {
+            hec_dss_zopen(ifltab.as_mut_slice().as_mut_ptr(),path.as_ptr());
+            err = err.update();
             err.is_ok()?;
             let version = zgetVersion(ifltab.as_mut_slice().as_mut_ptr());
             Ok(HecDss{ifltab: ifltab,
                       filename: dss_file,
-                      version: version})
+                      version: version,
+                      open: true})
+        }
+    }
+
+    // Snapshot of the records selected by filter. With use_crc, records rewritten with identical data are not reported as changed.
+    #[cfg_attr(feature="threadsafe",nonparallel(MUTX))]
+    pub fn track_changes(&mut self,filter:Option<PathnamePattern>,use_crc:bool) -> DssResult<ChangeTracker> {
+        let cfilter = ChangeTracker::native_filter(&filter)?;
+        let mut err = DssError::new();
+        unsafe {
+            let cat = zstructCatalogNew();
+            if cat.is_null() {
+                Err(DssError::raise("Error occured with allocation of underlying catalog object".to_string()))?;
+            }
+            let status = zwhatChangedSetStart(self.ifltab.as_mut_ptr(),cat,
+                                              cfilter.as_ref().map_or(std::ptr::null(),|x| x.as_ptr()),
+                                              use_crc as c_int);
+            err = err.update();
+            if status < 0 || err.is_ok().is_err() {
+                zstructFree(cat as *mut c_void);
+                Err(DssError::raise(format!("Error status {} returned while taking snapshot of {}",status,self.filename)))?;
+            }
+            let pathnames = (0..(*cat).numberPathnames as usize)
+                .map(|i| CStr::from_ptr(*(*cat).pathnameList.add(i)).to_string_lossy().into_owned())
+                .collect::<HashSet<_>>();
+            Ok(ChangeTracker{baseline:cat,filename:self.filename.clone(),pathnames:pathnames,filter:filter,use_crc:use_crc})
+        }
+    }
+
+    // Records added or modified since the snapshot of tracker was taken
+    #[cfg_attr(feature="threadsafe",nonparallel(MUTX))]
+    pub fn changes(&mut self,tracker:&ChangeTracker) -> DssResult<Vec<RecordChange>> {
+        if tracker.filename != self.filename {
+            Err(DssError::raise(format!("Snapshot was taken from {}, not {}",tracker.filename,self.filename)))?;
+        }
+        let cfilter = ChangeTracker::native_filter(&tracker.filter)?;
+        let mut err = DssError::new();
+        let mut changes = Vec::<RecordChange>::new();
+        unsafe {
+            let cat = zstructCatalogNew();
+            if cat.is_null() {
+                Err(DssError::raise("Error occured with allocation of underlying catalog object".to_string()))?;
+            }
+            let status = zwhatChangedCompare(self.ifltab.as_mut_ptr(),tracker.baseline,cat,
+                                             cfilter.as_ref().map_or(std::ptr::null(),|x| x.as_ptr()),
+                                             tracker.use_crc as c_int);
+            err = err.update();
+            if status < 0 || err.is_ok().is_err() {
+                zstructFree(cat as *mut c_void);
+                Err(DssError::raise(format!("Error status {} returned while comparing {} with snapshot",status,self.filename)))?;
+            }
+            for i in 0..(*cat).numberPathnames as usize {
+                let cpath = CStr::from_ptr(*(*cat).pathnameList.add(i)).to_string_lossy().into_owned();
+                let pathname = match DssPathname::from_string(&cpath) {
+                    Some(x) => x,
+                    None => continue
+                };
+                if let Some(ref filter) = tracker.filter {
+                    if !filter.matches(&pathname) {
+                        continue
+                    }
+                }
+                let kind = if tracker.pathnames.contains(&cpath) {ChangeKind::modified} else {ChangeKind::added};
+                changes.push(RecordChange{pathname:pathname,kind:kind});
+            }
+            zstructFree(cat as *mut c_void);
+        }
+        Ok(changes)
+    }
+
+    #[cfg_attr(feature="threadsafe",nonparallel(MUTX))]
+    pub fn add_alias(&mut self,primary:&DssPathname,alias:&DssPathname) -> DssResult<()> {
+        let cprimary = CString::new(primary.to_string()).map_err(|e| DssError::raise(e.to_string()))?;
+        let calias = CString::new(alias.to_string()).map_err(|e| DssError::raise(e.to_string()))?;
+        if !self.record_exists(&cprimary) {
+            Err(DssError::raise_kind(DssErrorKind::RECORD_DOES_NOT_EXIST,
+                                     format!("Record does not exist: {}",primary.to_string())))?;
+        }
+        if self.record_exists(&calias) {
+            Err(DssError::raise_kind(DssErrorKind::RECORD_ALREADY_EXISTS,
+                                     format!("Record already exists: {}",alias.to_string())))?;
+        }
+        let status = unsafe {zaliasAdd(self.ifltab.as_mut_ptr(),cprimary.as_ptr(),calias.as_ptr())};
+        if status != 0 {
+            Err(DssError::raise(format!("Error status {} returned while adding alias {} to {}",
+                                        status,alias.to_string(),primary.to_string())))?;
+        }
+        Ok(())
+    }
+
+    #[cfg_attr(feature="threadsafe",nonparallel(MUTX))]
+    pub fn remove_alias(&mut self,alias:&DssPathname) -> DssResult<()> {
+        let calias = CString::new(alias.to_string()).map_err(|e| DssError::raise(e.to_string()))?;
+        let status = unsafe {zaliasRemove(self.ifltab.as_mut_ptr(),calias.as_ptr())};
+        if status != 0 {
+            Err(DssError::raise(format!("Error status {} returned while removing alias {}",status,alias.to_string())))?;
+        }
+        Ok(())
+    }
+
+    // Removes every alias of the record that dss_path (primary or alias) refers to
+    #[cfg_attr(feature="threadsafe",nonparallel(MUTX))]
+    pub fn remove_all_aliases(&mut self,dss_path:&DssPathname) -> DssResult<()> {
+        let cpath = CString::new(dss_path.to_string()).map_err(|e| DssError::raise(e.to_string()))?;
+        let status = unsafe {zaliasRemoveAll(self.ifltab.as_mut_ptr(),cpath.as_ptr())};
+        if status != 0 {
+            Err(DssError::raise(format!("Error status {} returned while removing aliases of {}",status,dss_path.to_string())))?;
+        }
+        Ok(())
+    }
+
+    #[cfg_attr(feature="threadsafe",nonparallel(MUTX))]
+    pub fn primary_pathname(&mut self,alias:&DssPathname) -> DssResult<DssPathname> {
+        let calias = CString::new(alias.to_string()).map_err(|e| DssError::raise(e.to_string()))?;
+        // MAX_PATHNAME_SIZE in zdssVals.h
+        let mut primary = [0 as c_char;394];
+        let status = unsafe {zaliasGetPrimary(self.ifltab.as_mut_ptr(),calias.as_ptr(),primary.as_mut_ptr(),primary.len())};
+        if status != 0 {
+            Err(DssError::raise_kind(DssErrorKind::RECORD_DOES_NOT_EXIST,
+                                     format!("Unable to find primary pathname of {}, status {}",alias.to_string(),status)))?;
+        }
+        let primary = unsafe {CStr::from_ptr(primary.as_ptr())}.to_string_lossy().into_owned();
+        DssPathname::from_string(&primary)
+            .ok_or_else(|| DssError::raise(format!("Invalid primary pathname {} returned for {}",primary,alias.to_string())))
+    }
+
+    // Primary pathname and aliases of the record that dss_path refers to
+    #[cfg_attr(feature="threadsafe",nonparallel(MUTX))]
+    pub fn aliases(&mut self,dss_path:&DssPathname) -> DssResult<Vec<DssPathname>> {
+        let cpath = CString::new(dss_path.to_string()).map_err(|e| DssError::raise(e.to_string()))?;
+        let mut list:*mut c_char = std::ptr::null_mut();
+        let mut length:c_int = 0;
+        let status = unsafe {zaliasList(self.ifltab.as_mut_ptr(),cpath.as_ptr(),&mut list,&mut length)};
+        if status < 0 {
+            Err(DssError::raise(format!("Error status {} returned while listing aliases of {}",status,dss_path.to_string())))?;
+        }
+        if list.is_null() {
+            return Ok(Vec::new())
+        }
+        // null separated pathnames
+        let bytes = unsafe {std::slice::from_raw_parts(list as *const u8,length.max(0) as usize)}.to_vec();
+        // heclib mallocs the list and leaves it to the caller to free
+        unsafe {libc::free(list as *mut libc::c_void)};
+        Ok(bytes.split(|x| *x == 0)
+                .filter(|x| !x.is_empty())
+                .filter_map(|x| DssPathname::from_string(&String::from_utf8_lossy(x)))
+                .collect())
+    }
+
+    #[cfg_attr(feature="threadsafe",nonparallel(MUTX))]
+    pub fn record_info(&mut self,dss_path:&DssPathname) -> DssResult<RecordInfo> {
+        let path = CString::new(dss_path.to_string()).map_err(|e| DssError::raise(e.to_string()))?;
+        if !self.record_exists(&path) {
+            Err(DssError::raise_kind(DssErrorKind::RECORD_DOES_NOT_EXIST,
+                                     format!("Record does not exist: {}",dss_path.to_string())))?;
+        }
+        let mut err = DssError::new();
+        unsafe {
+            let zrb = zstructRecordBasicsNew(path.as_ptr());
+            if zrb.is_null() {
+                Err(DssError::raise(format!("Error occured with allocation of record basics for {}",dss_path.to_string())))?;
+            }
+            let status = zgetRecordBasics(self.ifltab.as_mut_ptr(),zrb);
+            err = err.update();
+            if status != 0 || err.is_ok().is_err() {
+                zstructFree(zrb as *mut c_void);
+                Err(DssError::raise(format!("Error status {} returned while reading record basics of {}",status,dss_path.to_string())))?;
+            }
+            let zrs = zstructRecordSizeNew(path.as_ptr());
+            if zrs.is_null() {
+                zstructFree(zrb as *mut c_void);
+                Err(DssError::raise(format!("Error occured with allocation of record size for {}",dss_path.to_string())))?;
+            }
+            let status = zgetRecordSize(self.ifltab.as_mut_ptr(),zrs);
+            if status != 0 {
+                zstructFree(zrb as *mut c_void);
+                zstructFree(zrs as *mut c_void);
+                Err(DssError::raise(format!("Error status {} returned while reading record size of {}",status,dss_path.to_string())))?;
+            }
+            let record_type = RecordType::from_value((*zrb).recordType);
+            let ts_size = if record_type.is_time_series() {
+                Some(TimeSeriesRecordSize{precision:(*zrs).tsPrecision,
+                                          time_offset:(*zrs).tsTimeOffset,
+                                          profile_depths:(*zrs).tsProfileDepthsNumber,
+                                          block_start:(*zrs).tsBlockStartPosition,
+                                          block_end:(*zrs).tsBlockEndPosition,
+                                          value_size:(*zrs).tsValueSize,
+                                          value_element_size:(*zrs).tsValueElementSize,
+                                          quality_element_size:(*zrs).tsQualityElementSize,
+                                          inote_element_size:(*zrs).tsInotesElementSize,
+                                          cnotes_length:(*zrs).tsCnotesLength})
+            } else {
+                None
+            };
+            let pd_size = if record_type.is_paired_data() {
+                Some(PairedDataRecordSize{number_curves:(*zrs).pdNumberCurves,
+                                          number_ordinates:(*zrs).pdNumberOrdinates,
+                                          value_size:(*zrs).ipdValueSize,
+                                          independent_is_xaxis:(*zrs).pdBoolIndependentIsXaxis != 0,
+                                          labels_length:(*zrs).pdLabelsLength,
+                                          precision:(*zrs).pdPrecision})
+            } else {
+                None
+            };
+            let info = RecordInfo{pathname:dss_path.clone(),
+                                  record_type:record_type,
+                                  version:(*zrb).version,
+                                  number_values:(*zrb).numberValues,
+                                  logical_number_values:(*zrb).logicalNumberValues,
+                                  allocated_size:(*zrb).allocatedSize,
+                                  last_write_time:(*zrb).recLastWriteTimeMillis,
+                                  creation_time:(*zrb).recCreationTimeMillis,
+                                  program:CStr::from_ptr((*zrs).programLastWrite.as_ptr()).to_string_lossy().trim().to_string(),
+                                  ts_size:ts_size,
+                                  pd_size:pd_size};
+            zstructFree(zrb as *mut c_void);
+            zstructFree(zrs as *mut c_void);
+            Ok(info)
+        }
+    }
+
+    /// Runs the heclib file, hash table, link and pathname checks.
+    ///
+    /// The report has the error count of every check and one issue per failed check.
+    /// heclib does not expose the individual failures a check finds, only the last
+    /// error it raised, so an issue names the last bad pathname/address rather than
+    /// all of them. The full list is only written to the heclib message log.
+    #[cfg_attr(feature="threadsafe",nonparallel(MUTX))]
+    pub fn verify(&mut self) -> DssResult<IntegrityReport> {
+        let mut report = IntegrityReport{checks:Vec::new(),issues:Vec::new()};
+        let checks:[(IntegrityCheck,unsafe extern "C" fn(*mut i64) -> c_int);4] = [
+            (IntegrityCheck::file,zcheckFile),
+            (IntegrityCheck::hash_table,zcheckHashTable),
+            (IntegrityCheck::links,zcheckLinks),
+            (IntegrityCheck::pathnames,zcheckPathnames)];
+        for (check,func) in checks.iter() {
+            let status = unsafe {
+                zerrorStructClear();
+                func(self.ifltab.as_mut_ptr())
+            };
+            report.checks.push((*check,status));
+            if status == 0 {
+                continue
+            }
+            let err = DssError::new().update();
+            let kind = match err.kind() {
+                DssErrorKind::STATUS_OK | DssErrorKind::UNDEFINED_ERROR => DssErrorKind::DAMAGED_FILE,
+                x => x.clone()
+            };
+            let message = match err.message() {
+                "" => format!("{} check reported status {}",check.to_string(),status),
+                x => x.to_string()
+            };
+            report.issues.push(IntegrityIssue{check:*check,
+                                              kind:kind,
+                                              message:message,
+                                              last_pathname:err.last_pathname(),
+                                              last_address:err.last_address()});
+        }
+        Ok(report)
+    }
+
+    #[cfg_attr(feature="threadsafe",nonparallel(MUTX))]
+    pub fn needs_squeeze(&mut self) -> DssResult<bool> {
+        self.check_open()?;
+        self.squeeze_needed()
+    }
+
+    // Rewrites the file without deleted or wasted space, then closes and reopens the handle on the squeezed file.
+    // If the file cannot be reopened the handle stays closed and the HecDss should be dropped.
+    #[cfg_attr(feature="threadsafe",nonparallel(MUTX))]
+    pub fn squeeze(&mut self,only_if_needed:bool) -> DssResult<()> {
+        self.check_open()?;
+        // squeeze into a new file rather than in place
+        let status = unsafe {zsqueeze7(self.ifltab.as_mut_ptr(),only_if_needed as c_int,0)};
+        if status != 0 {
+            Err(DssError::raise_kind(DssErrorKind::CANNOT_SQUEEZE,
+                                     format!("Error status {} returned while squeezing {}",status,self.filename)))?;
+        }
+        let path = CString::new(self.filename.clone()).map_err(|e| DssError::raise(e.to_string()))?;
+        let status = unsafe {
+            zclose(self.ifltab.as_mut_ptr());
+            self.ifltab = [0i64;500];
+            hec_dss_zopen(self.ifltab.as_mut_ptr(),path.as_ptr())
+        };
+        if status != 0 {
+            self.open = false;
+            Err(DssError::raise_kind(DssErrorKind::CANNOT_SQUEEZE,
+                                     format!("Error status {} returned while reopening {} after squeeze",status,self.filename)))?;
+        }
+        self.version = unsafe {zgetVersion(self.ifltab.as_mut_ptr())};
+        Ok(())
+    }
+
+    // Adds amount_longs words at address to the free space list, for space known to be unused
+    #[cfg_attr(feature="threadsafe",nonparallel(MUTX))]
+    pub fn release_file_space(&mut self,address:i64,amount_longs:c_int) -> DssResult<()> {
+        self.check_open()?;
+        if address <= 0 || amount_longs <= 0 {
+            Err(DssError::raise_kind(DssErrorKind::CANNOT_SQUEEZE,
+                                     format!("Invalid file space to release, address {} size {}",address,amount_longs)))?;
+        }
+        unsafe {zreleaseFileSpace(self.ifltab.as_mut_ptr(),address,amount_longs)};
+        Ok(())
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    fn check_open(&self) -> DssResult<()> {
+        if !self.open {
+            Err(DssError::raise(format!("{} is closed",self.filename)))?;
+        }
+        Ok(())
+    }
+
+    fn squeeze_needed(&mut self) -> DssResult<bool> {
+        let status = unsafe {zsqueezeNeeded(self.ifltab.as_mut_ptr())};
+        if status < 0 {
+            Err(DssError::raise_kind(DssErrorKind::CANNOT_SQUEEZE,
+                                     format!("Error status {} returned while checking {} for squeeze",status,self.filename)))?;
         }
+        Ok(status > 0)
     }
 
     #[cfg_attr(feature="threadsafe",nonparallel(MUTX))]
@@ -837,7 +2812,318 @@ impl HecDss {
     }
 
     #[cfg_attr(feature="threadsafe",nonparallel(MUTX))]
-    pub fn read_ts(&mut self,dss_path:DssPathname,retflag:Option<c_int>,as_double:Option<bool>,alltime:Option<bool>) -> DssResult<TimeSeriesContainer> {
+    pub fn copy_to(&mut self,other:&mut HecDss,dss_path_in:DssPathname,dss_path_out:DssPathname) -> DssResult<()> {
+        self.copy_record_to(other,&dss_path_in,&dss_path_out)
+    }
+
+    // Copies the records selected by filter, or the whole file, into other and returns the number of records copied
+    #[cfg_attr(feature="threadsafe",nonparallel(MUTX))]
+    pub fn copy_file(&mut self,other:&mut HecDss,filter:Option<PathnamePattern>) -> DssResult<usize> {
+        match filter {
+            Some(filter) => {
+                let mut options = CatalogOptions::new();
+                options.set_filter(filter);
+                let entries = self.catalog_entries(options)?;
+                for entry in &entries {
+                    self.copy_record_to(other,&entry.pathname,&entry.pathname)?;
+                }
+                Ok(entries.len())
+            },
+            None => {
+                let count = self.catalog_entries(CatalogOptions::new())?.len();
+                let mut err = DssError::new();
+                unsafe {
+                    // statusWanted = 0 copies valid records only
+                    let status = zcopyFile(self.ifltab.as_mut_ptr(),other.ifltab.as_mut_ptr(),0);
+                    err = err.update();
+                    err.is_ok()?;
+                    if status != 0 {
+                        Err(DssError::raise(format!("Error status {} returned while copying {} to {}",
+                                                    status,self.filename,other.filename)))?;
+                    }
+                }
+                Ok(count)
+            }
+        }
+    }
+
+    fn copy_record_to(&mut self,other:&mut HecDss,dss_path_in:&DssPathname,dss_path_out:&DssPathname) -> DssResult<()> {
+        let mut err = DssError::new();
+        let from = CString::new(dss_path_in.to_string()).map_err(|e| DssError::raise(e.to_string()))?;
+        let to = CString::new(dss_path_out.to_string()).map_err(|e| DssError::raise(e.to_string()))?;
+        unsafe {
+            let status = zcopyRecord(self.ifltab.as_mut_ptr(),other.ifltab.as_mut_ptr(),from.as_ptr(),to.as_ptr());
+            err = err.update();
+            err.is_ok()?;
+            if status != 0 {
+                Err(DssError::raise(format!("Error status {} returned while copying {} to {}",
+                                            status,dss_path_in.to_string(),other.filename)))?;
+            }
+        }
+        Ok(())
+    }
+
+    #[cfg_attr(feature="threadsafe",nonparallel(MUTX))]
+    pub fn catalog(&mut self,options:Option<CatalogOptions>) -> DssResult<Vec<CatalogEntry>> {
+        self.catalog_entries(options.unwrap_or(CatalogOptions::new()))
+    }
+
+    #[cfg_attr(feature="threadsafe",nonparallel(MUTX))]
+    pub fn condensed_catalog(&mut self,options:Option<CatalogOptions>) -> DssResult<Vec<CondensedPathname>> {
+        let entries = self.catalog_entries(options.unwrap_or(CatalogOptions::new()))?;
+        let mut condensed = Vec::<CondensedPathname>::new();
+        // position in condensed of each time-series pathname without its D-part
+        let mut groups = HashMap::<String,usize>::new();
+        for entry in entries {
+            if !entry.record_type.is_time_series() {
+                condensed.push(CondensedPathname{pathname:entry.pathname.clone(),
+                                                 first:entry.pathname.clone(),
+                                                 last:entry.pathname,
+                                                 record_type:entry.record_type,
+                                                 block_count:1,
+                                                 time_range:None});
+                continue
+            }
+            let mut key = entry.pathname.clone();
+            key.dpart = None;
+            match groups.get(&key.to_string()) {
+                Some(&i) => condensed[i].block_count += 1,
+                None => {
+                    groups.insert(key.to_string(),condensed.len());
+                    condensed.push(CondensedPathname{pathname:key,
+                                                     first:entry.pathname.clone(),
+                                                     last:entry.pathname,
+                                                     record_type:entry.record_type,
+                                                     block_count:1,
+                                                     time_range:None})
+                }
+            }
+        }
+        for item in condensed.iter_mut().filter(|x| x.record_type.is_time_series()) {
+            if item.block_count > 1 {
+                let (first,last) = self.first_last_pathnames(&item.first)?;
+                item.first = first;
+                item.last = last;
+            }
+            item.time_range = Some(self.ts_time_range(&item.first)?);
+        }
+        Ok(condensed)
+    }
+
+    fn ts_time_range(&mut self,dss_path:&DssPathname) -> DssResult<(HecTime,HecTime)> {
+        let path = CString::new(dss_path.to_string()).map_err(|e| DssError::raise(e.to_string()))?;
+        let (mut first_julian,mut first_seconds,mut last_julian,mut last_seconds) = (0,0,0,0);
+        let status = unsafe {
+            ztsGetDateTimeRange(self.ifltab.as_mut_ptr(),path.as_ptr(),1,
+                                &mut first_julian,&mut first_seconds,
+                                &mut last_julian,&mut last_seconds)
+        };
+        if status != 0 {
+            Err(DssError::raise(format!("Unable to determine time range of {}",dss_path.to_string())))?;
+        }
+        Ok((HecTime{value:first_seconds,granularity:HecTimeGranularity::second,basedate_days:first_julian},
+            HecTime{value:last_seconds,granularity:HecTimeGranularity::second,basedate_days:last_julian}))
+    }
+
+    fn first_last_pathnames(&mut self,seed:&DssPathname) -> DssResult<(DssPathname,DssPathname)> {
+        let path = CString::new(seed.to_string()).map_err(|e| DssError::raise(e.to_string()))?;
+        // MAX_PATHNAME_SIZE in zdssVals.h
+        let mut first = [0 as c_char;394];
+        let mut last = [0 as c_char;394];
+        let status = unsafe {
+            ztsGetFirstLastPathnames(self.ifltab.as_mut_ptr(),path.as_ptr(),
+                                     first.as_mut_ptr(),first.len(),
+                                     last.as_mut_ptr(),last.len())
+        };
+        if status < 0 {
+            Err(DssError::raise(format!("Unable to find first and last pathnames of {}",seed.to_string())))?;
+        }
+        let (first,last) = unsafe {
+            (CStr::from_ptr(first.as_ptr()).to_string_lossy().into_owned(),
+             CStr::from_ptr(last.as_ptr()).to_string_lossy().into_owned())
+        };
+        match (DssPathname::from_string(&first),DssPathname::from_string(&last)) {
+            (Some(x),Some(y)) => Ok((x,y)),
+            _ => Err(DssError::raise(format!("Invalid first/last pathnames returned for {}",seed.to_string())))
+        }
+    }
+
+    fn catalog_entries(&mut self,options:CatalogOptions) -> DssResult<Vec<CatalogEntry>> {
+        let mut err = DssError::new();
+        let mut entries = Vec::<CatalogEntry>::new();
+        unsafe {
+            let cat = zstructCatalogNew();
+            if cat.is_null() {
+                Err(DssError::raise("Error occured with allocation of underlying catalog object".to_string()))?;
+            }
+            (*cat).boolIncludeDates = options.include_dates as c_int;
+            (*cat).boolGetCRCvalues = options.include_crc as c_int;
+            // only patterns understood by heclib are pushed down, the rest are matched below
+            let cfilter = match options.filter {
+                Some(ref x) if x.is_native() => Some(CString::new(x.to_string()).map_err(|e| DssError::raise(e.to_string()))?),
+                _ => None
+            };
+            let cfilter_ptr = cfilter.as_ref().map_or(std::ptr::null(),|x| x.as_ptr());
+            let count = zcatalog(self.ifltab.as_mut_ptr(),cfilter_ptr,cat,options.sorted as c_int);
+            err = err.update();
+            if let Err(e) = err.is_ok() {
+                zstructFree(cat as *mut c_void);
+                return Err(e)
+            }
+            if count < 0 {
+                zstructFree(cat as *mut c_void);
+                Err(DssError::raise(format!("Error status {} returned while cataloging {}",count,self.filename)))?;
+            }
+            let count = (*cat).numberPathnames as usize;
+            for i in 0..count {
+                let cpath = CStr::from_ptr(*(*cat).pathnameList.add(i)).to_string_lossy();
+                let pathname = match DssPathname::from_string(&cpath) {
+                    Some(x) => x,
+                    None => continue
+                };
+                if let Some(ref filter) = options.filter {
+                    if !filter.matches(&pathname) {
+                        continue
+                    }
+                }
+                let record_type = if (*cat).recordType.is_null() {
+                    RecordType::undefined(0)
+                } else {
+                    RecordType::from_value(*(*cat).recordType.add(i))
+                };
+                let dates = if options.include_dates && !(*cat).startDates.is_null() && !(*cat).endDates.is_null() {
+                    Some((*(*cat).startDates.add(i),*(*cat).endDates.add(i)))
+                } else {
+                    None
+                };
+                let last_write_time = if (*cat).lastWriteTimeRecord.is_null() {
+                    None
+                } else {
+                    Some(*(*cat).lastWriteTimeRecord.add(i))
+                };
+                let crc = if options.include_crc && !(*cat).crcValues.is_null() {
+                    Some(*(*cat).crcValues.add(i))
+                } else {
+                    None
+                };
+                entries.push(CatalogEntry{pathname:pathname,
+                                          record_type:record_type,
+                                          dates:dates,
+                                          last_write_time:last_write_time,
+                                          crc:crc});
+            }
+            zstructFree(cat as *mut c_void);
+        }
+        Ok(entries)
+    }
+
+    #[cfg_attr(feature="threadsafe",nonparallel(MUTX))]
+    pub fn delete(&mut self,dss_path:&DssPathname) -> DssResult<()> {
+        self.delete_record(dss_path)
+    }
+
+    // Deletes the records in order and returns their pathnames, stopping at the first failure
+    #[cfg_attr(feature="threadsafe",nonparallel(MUTX))]
+    pub fn delete_many(&mut self,dss_paths:&[DssPathname]) -> DssResult<Vec<DssPathname>> {
+        self.delete_records(dss_paths)?;
+        Ok(dss_paths.to_vec())
+    }
+
+    // Deletes every record selected by the catalog filter and returns their pathnames
+    #[cfg_attr(feature="threadsafe",nonparallel(MUTX))]
+    pub fn delete_matching(&mut self,filter:PathnamePattern) -> DssResult<Vec<DssPathname>> {
+        let mut options = CatalogOptions::new();
+        options.set_filter(filter);
+        let dss_paths = self.catalog_entries(options)?.into_iter().map(|x| x.pathname).collect::<Vec<_>>();
+        self.delete_records(&dss_paths)?;
+        Ok(dss_paths)
+    }
+
+    fn delete_records(&mut self,dss_paths:&[DssPathname]) -> DssResult<()> {
+        for (i,dss_path) in dss_paths.iter().enumerate() {
+            self.delete_record(dss_path).map_err(|e| {
+                let deleted = dss_paths[..i].iter().map(|x| x.to_string()).collect::<Vec<_>>();
+                DssError::raise_kind(e.kind().clone(),format!("Deleted {} of {} records {:?}, {}",i,dss_paths.len(),deleted,e.message()))
+            })?;
+        }
+        Ok(())
+    }
+
+    #[cfg_attr(feature="threadsafe",nonparallel(MUTX))]
+    pub fn undelete(&mut self,dss_path:&DssPathname) -> DssResult<()> {
+        self.undelete_record(dss_path)
+    }
+
+    // Undeletes the records in order and returns their pathnames, stopping at the first failure
+    #[cfg_attr(feature="threadsafe",nonparallel(MUTX))]
+    pub fn undelete_many(&mut self,dss_paths:&[DssPathname]) -> DssResult<Vec<DssPathname>> {
+        for (i,dss_path) in dss_paths.iter().enumerate() {
+            self.undelete_record(dss_path).map_err(|e| {
+                DssError::raise_kind(e.kind().clone(),format!("Undeleted {} of {} records, {}",i,dss_paths.len(),e.message()))
+            })?;
+        }
+        Ok(dss_paths.to_vec())
+    }
+
+    #[cfg_attr(feature="threadsafe",nonparallel(MUTX))]
+    pub fn rename(&mut self,old_path:&DssPathname,new_path:&DssPathname) -> DssResult<()> {
+        let old_cpath = CString::new(old_path.to_string()).map_err(|e| DssError::raise(e.to_string()))?;
+        let new_cpath = CString::new(new_path.to_string()).map_err(|e| DssError::raise(e.to_string()))?;
+        if !self.record_exists(&old_cpath) {
+            Err(DssError::raise_kind(DssErrorKind::RECORD_DOES_NOT_EXIST,
+                                     format!("Record does not exist: {}",old_path.to_string())))?;
+        }
+        if self.record_exists(&new_cpath) {
+            Err(DssError::raise_kind(DssErrorKind::RECORD_ALREADY_EXISTS,
+                                     format!("Record already exists: {}",new_path.to_string())))?;
+        }
+        let status = unsafe {zrename(self.ifltab.as_mut_ptr(),old_cpath.as_ptr(),new_cpath.as_ptr())};
+        if status != 0 {
+            Err(DssError::raise(format!("Error status {} returned while renaming {} to {}",
+                                        status,old_path.to_string(),new_path.to_string())))?;
+        }
+        Ok(())
+    }
+
+    fn record_exists(&mut self,cpath:&CStr) -> bool {
+        // STATUS_RECORD_FOUND = 0
+        unsafe {zcheck(self.ifltab.as_mut_ptr(),cpath.as_ptr()) == 0}
+    }
+
+    fn delete_record(&mut self,dss_path:&DssPathname) -> DssResult<()> {
+        let cpath = CString::new(dss_path.to_string()).map_err(|e| DssError::raise(e.to_string()))?;
+        if !self.record_exists(&cpath) {
+            Err(DssError::raise_kind(DssErrorKind::RECORD_DOES_NOT_EXIST,
+                                     format!("Record does not exist: {}",dss_path.to_string())))?;
+        }
+        let status = unsafe {zdelete(self.ifltab.as_mut_ptr(),cpath.as_ptr())};
+        if status != 0 {
+            Err(DssError::raise(format!("Error status {} returned while deleting {}",status,dss_path.to_string())))?;
+        }
+        Ok(())
+    }
+
+    fn undelete_record(&mut self,dss_path:&DssPathname) -> DssResult<()> {
+        let cpath = CString::new(dss_path.to_string()).map_err(|e| DssError::raise(e.to_string()))?;
+        if self.record_exists(&cpath) {
+            Err(DssError::raise_kind(DssErrorKind::RECORD_ALREADY_EXISTS,
+                                     format!("Record already exists: {}",dss_path.to_string())))?;
+        }
+        let status = unsafe {zundelete(self.ifltab.as_mut_ptr(),cpath.as_ptr())};
+        if status != 0 {
+            let err = DssError::new().update();
+            let kind = match err.kind() {
+                DssErrorKind::NO_UNDELETE_WITH_RECLAIM => DssErrorKind::NO_UNDELETE_WITH_RECLAIM,
+                _ => DssErrorKind::RECORD_DOES_NOT_EXIST
+            };
+            Err(DssError::raise_kind(kind,format!("Unable to undelete {}, status {}",dss_path.to_string(),status)))?;
+        }
+        Ok(())
+    }
+
+    #[cfg_attr(feature="threadsafe",nonparallel(MUTX))]
+    pub fn read_ts(&mut self,dss_path:impl Into<DssPathname>,retflag:Option<c_int>,as_double:Option<bool>,alltime:Option<bool>) -> DssResult<TimeSeriesContainer> {
+        let dss_path:DssPathname = dss_path.into();
         let mut err = DssError::new();
         unsafe {
             let path = CString::new(dss_path.to_string()).expect("error with dss pathname");
@@ -848,149 +3134,389 @@ impl HecDss {
                 Some(-1) => -1,
                 _ => -1
             };
-            // check zts.is_null() ??
+            if zts.is_null() {
+                Err(DssError::raise("Error occured with allocation of underlying time-series object".to_string()))?;
+            }
+            // without a D-part there is no block to restrict the read to
+            let no_dpart = dss_path.dpart.as_ref().map_or(true,|x| x.trim().is_empty());
             match alltime {
-                Some(true) => (*zts).boolRetrieveAllTimes = 1,
-                Some(false) => (*zts).boolRetrieveAllTimes = 0,
+                Some(false) if !no_dpart => (*zts).boolRetrieveAllTimes = 0,
                 _ => (*zts).boolRetrieveAllTimes = 1,
             };
 
-            let float_or_double = match as_double {
-                Some(true) => 1,
-                _ => 0
-            };
+            let float_or_double = float_or_double_flag(as_double);
 
             // read from dss
-            let status = ztsRetrieve(self.ifltab.as_mut_slice().as_mut_ptr(),zts,rflag,float_or_double,0);
+            let status = ztsRetrieve(self.ifltab.as_mut_slice().as_mut_ptr(),zts,rflag,float_or_double,1);
             err = err.update();
-            err.is_ok()?;
+            if let Err(e) = err.is_ok() {
+                zstructFree(zts as *mut c_void);
+                return Err(e)
+            }
+            if status != 0 {
+                zstructFree(zts as *mut c_void);
+                Err(DssError::raise(format!("Error status {} returned while reading time-series {}",status,dss_path.to_string())))?;
+            }
 
-            // Create the output container
-            let interval = (*zts).timeIntervalSeconds;
-            let ts_type = TimeSeriesType::from_interval(interval);
-            let data_count = (*zts).numberValues as c_int;
-            let _path = {let cpath = (*zts).pathname;
-                         let cpath = CStr::from_ptr(cpath);
-                         let cpath = cpath.to_str().unwrap();
-                         DssPathname::from_string(cpath)
-                        };
-            let mut tsc = TimeSeriesContainer::new(ts_type,data_count);
-            
-            // pathname
-            tsc.set_pathname(_path);
-
-            // set meta data here
-            let dtype = {
-                let dtype = (*zts).type_;
-                let datatype = CStr::from_ptr(dtype);
-                datatype.to_str().unwrap()
-            };
-            let unit = {
-                let unit = (*zts).units;
-                let dataunit = CStr::from_ptr(unit);
-                dataunit.to_str().unwrap()
+            let tsc = TimeSeriesContainer::from_struct(zts);
+            zstructFree(zts as *mut c_void);
+            tsc
+        }
+    }
+
+    #[cfg_attr(feature="threadsafe",nonparallel(MUTX))]
+    pub fn read_ts_window(&mut self,dss_path:DssPathname,options:TimeSeriesOptions,as_double:Option<bool>) -> DssResult<TimeSeriesContainer> {
+        let mut err = DssError::new();
+        let path = CString::new(dss_path.to_string()).expect("error with dss pathname");
+        let (start_time,end_time) = match options.slice {
+            Some(ref slice) => (slice.start_time,slice.end_time),
+            None => (None,None)
+        };
+        unsafe {
+            let zts = match (start_time,end_time) {
+                (None,None) => {
+                    let zts = zstructTsNew(path.as_ptr());
+                    if !zts.is_null() {
+                        (*zts).boolRetrieveAllTimes = 1;
+                    }
+                    zts
+                },
+                _ => {
+                    // an open ended window is bounded by the first/last time in the record set
+                    let (mut first_julian,mut first_seconds,mut last_julian,mut last_seconds) = (0,0,0,0);
+                    if start_time.is_none() || end_time.is_none() {
+                        let status = ztsGetDateTimeRange(self.ifltab.as_mut_ptr(),path.as_ptr(),1,
+                                                         &mut first_julian,&mut first_seconds,
+                                                         &mut last_julian,&mut last_seconds);
+                        if status != 0 {
+                            Err(DssError::raise(format!("Unable to determine time range of {}",dss_path.to_string())))?;
+                        }
+                    }
+                    let start = start_time.unwrap_or(HecTime{value:first_seconds,granularity:HecTimeGranularity::second,basedate_days:first_julian});
+                    let end = end_time.unwrap_or(HecTime{value:last_seconds,granularity:HecTimeGranularity::second,basedate_days:last_julian});
+                    let (start_date,start_clock) = start.to_string().ok_or_else(|| DssError::raise("Invalid start time of time window".to_string()))?;
+                    let (end_date,end_clock) = end.to_string().ok_or_else(|| DssError::raise("Invalid end time of time window".to_string()))?;
+                    let start_date = CString::new(start_date).unwrap();
+                    let start_clock = CString::new(start_clock).unwrap();
+                    let end_date = CString::new(end_date).unwrap();
+                    let end_clock = CString::new(end_clock).unwrap();
+                    zstructTsNewTimes(path.as_ptr(),start_date.as_ptr(),start_clock.as_ptr(),
+                                      end_date.as_ptr(),end_clock.as_ptr())
+                }
             };
-            tsc.set_type(dtype);
-            tsc.set_unit(unit);
+            if zts.is_null() {
+                Err(DssError::raise("Error occured with allocation of underlying time-series object".to_string()))?;
+            }
 
-            // set data
-            let buf_ptr:*const f32 = (*zts).floatValues;
-            if !buf_ptr.is_null() {
-                tsc.set_values(std::slice::from_raw_parts(buf_ptr, data_count as usize));
+            let float_or_double = float_or_double_flag(as_double);
+            let status = ztsRetrieve(self.ifltab.as_mut_ptr(),zts,0,float_or_double,1);
+            err = err.update();
+            if let Err(e) = err.is_ok() {
+                zstructFree(zts as *mut c_void);
+                return Err(e)
+            }
+            if status != 0 {
+                zstructFree(zts as *mut c_void);
+                Err(DssError::raise(format!("Error status {} returned while reading time-series {}",status,dss_path.to_string())))?;
             }
 
-            // set time values
-            let granularity = HecTimeGranularity::from_value((*zts).timeGranularitySeconds);
-            let mut basedate = 0 as c_int;
-            let mut times = Vec::<HecTime>::with_capacity(data_count as usize);
-            match ts_type {
-                TimeSeriesType::irregular => {
-                    tsc.set_interval(None);
-                    basedate = (*zts).julianBaseDate;
-                    let buf_ptr = (*zts).times;
-                    if !buf_ptr.is_null() {
-                        let buf = std::slice::from_raw_parts(buf_ptr, data_count as usize);
-                        for x in buf {
-                            times.push(HecTime{value:*x,granularity:granularity,basedate_days:basedate});
-                        }
-                        tsc.set_times(times.as_slice());
+            match options.trim_flags() {
+                (true,true) => {ztsTrim(self.ifltab.as_mut_ptr(),zts);},
+                (false,false) => {},
+                (trim_start,trim_end) => {
+                    let count = (*zts).numberValues as usize;
+                    let missing:Vec<bool> = if !(*zts).doubleValues.is_null() {
+                        std::slice::from_raw_parts((*zts).doubleValues,count).iter().map(|x| zisMissingDouble(*x) != 0).collect()
+                    } else if !(*zts).floatValues.is_null() {
+                        std::slice::from_raw_parts((*zts).floatValues,count).iter().map(|x| zisMissingFloat(*x) != 0).collect()
+                    } else {
+                        Vec::new()
+                    };
+                    match (missing.iter().position(|x| !x),missing.iter().rposition(|x| !x)) {
+                        (Some(first),Some(last)) => {
+                            let first = if trim_start {first} else {0};
+                            let last = if trim_end {last} else {count-1};
+                            ztsTrimAdjustTimeWindow(self.ifltab.as_mut_ptr(),zts,first as c_int,last as c_int);
+                        },
+                        // nothing but missing values, trimming either end empties the data
+                        _ => {ztsTrim(self.ifltab.as_mut_ptr(),zts);}
                     }
-                },
-                TimeSeriesType::regular => {
-                    tsc.set_interval(Some(HecTimeInterval::second(interval)));
-                    basedate = (*zts).startJulianDate;
-                    let value = (((*zts).startTimeSeconds as f32)/(granularity.value() as f32)) as c_int;
-                    let htime = HecTime{value:value,granularity:granularity,basedate_days:basedate};
-                    times.push(htime);
-                    tsc.set_times(times.as_slice());
                 }
             }
+
+            let tsc = TimeSeriesContainer::from_struct(zts);
             zstructFree(zts as *mut c_void);
-            Ok(tsc)
+            tsc
+        }
+    }
+
+    #[cfg_attr(feature="threadsafe",nonparallel(MUTX))]
+    pub fn iter_ts(&mut self,dss_path:DssPathname,start:HecTime,end:HecTime) -> DssResult<TimeSeriesIter> {
+        let path = CString::new(dss_path.to_string()).expect("error with dss pathname");
+        let epart = dss_path.epart.clone().unwrap_or_default();
+        // Skip blocks outside of the stored record set
+        let (mut first_julian,mut first_seconds,mut last_julian,mut last_seconds) = (0,0,0,0);
+        let status = unsafe {
+            ztsGetFirstLastRecordTimes(self.ifltab.as_mut_ptr(),path.as_ptr(),
+                                       &mut first_julian,&mut first_seconds,
+                                       &mut last_julian,&mut last_seconds,1)
+        };
+        if status != 0 {
+            Err(DssError::raise(format!("Unable to determine time range of {}",dss_path.to_string())))?;
         }
+        let start = std::cmp::max(start.to_julian_seconds(),(first_julian,first_seconds));
+        let end = std::cmp::min(end.to_julian_seconds(),(last_julian,last_seconds));
+        let (block_julian,block_size) = match HecTimeIntervalIreg::from_string(&epart) {
+            Some(block) => {
+                let size = block.block_size();
+                (unsafe {ztsIrregGetBlockStart(start.0,size)},size)
+            },
+            None => {
+                let interval = HecTimeInterval::from_string(&epart)
+                                .ok_or_else(|| DssError::raise(format!("Invalid time-series E-part {}",epart)))?;
+                let mut size = 0;
+                (unsafe {ztsRegGetBlockStart(start.0,interval.value(),&mut size)},size)
+            }
+        };
+        Ok(TimeSeriesIter{dss:self,
+                          pathname:path,
+                          block_size:block_size,
+                          block_julian:block_julian,
+                          start:start,
+                          end:end,
+                          done:start > end})
+    }
 
+    #[cfg_attr(feature="threadsafe",nonparallel(MUTX))]
+    pub fn put_ts(&mut self,tsc:TimeSeriesContainer,mode:Option<StoreMode>) -> DssResult<()> {
+        let mode = mode.unwrap_or(StoreMode::default(tsc.ts_type));
+        let storage_flag = mode.value(tsc.ts_type)?;
+        self.store_ts(tsc,storage_flag)
     }
 
     #[cfg_attr(feature="threadsafe",nonparallel(MUTX))]
-    pub fn put_ts(&mut self,tsc:TimeSeriesContainer,flag:Option<c_int>) -> DssResult<()> {
+    pub fn put_ts_irregular(&mut self,tsc:TimeSeriesContainer,block:HecTimeIntervalIreg,mode:StoreMode) -> DssResult<()> {
+        let mut ts = tsc;
+        if ts.ts_type != TimeSeriesType::irregular {
+            Err(DssError::raise("put_ts_irregular requires an irregular TimeSeriesContainer".to_string()))?;
+        }
+        let mut path = ts.pathname().ok_or_else(|| DssError::raise("Pathname not specified".to_string()))?;
+        match path.epart {
+            Some(ref x) if !x.trim().is_empty() => {
+                match HecTimeIntervalIreg::from_string(x) {
+                    Some(epart) if epart.block_size() == block.block_size() => {},
+                    Some(epart) => Err(DssError::raise(format!("E-part {} of pathname does not match irregular block size {}",epart.to_string(),block.to_string())))?,
+                    None => Err(DssError::raise(format!("Invalid irregular time-series E-part {}",x)))?
+                }
+            },
+            _ => {path.epart = Some(block.to_string());}
+        };
+        ts.set_pathname(Some(path));
+        let storage_flag = mode.value(ts.ts_type)?;
+        self.store_ts(ts,storage_flag)
+    }
+
+    fn store_ts(&mut self,tsc:TimeSeriesContainer,storage_flag:c_int) -> DssResult<()> {
         let mut ts = tsc;
         let mut err = DssError::new();
-        //let ts = &mut tsc;
         let path = match &ts.pathname {
                     Some(pathname) => CString::new(pathname.to_string()).unwrap(),
                     _ => Err(DssError::raise("Pathname not specified".to_string()))?,
             };
-        let storage_flag = match flag {
-            Some(x) => x,
-            _ => 0
-        };
-        
+
         let mut status = 0 as i32;
-        let unit = CString::new(ts.unit().to_string()).unwrap();
-        let typ = CString::new(ts.dtype().to_string()).unwrap();
+        let unit = CString::new(ts.data_unit.clone()).unwrap();
+        let typ = CString::new(ts.data_type.clone()).unwrap();
         let count = ts.len() as i32;
         let times = &ts.times(false).expect("Times or start_time not specified for TimeSeries Container");
+        // zts keeps a pointer to the times array, so it must outlive the store call
+        let mut itimes = times.iter().map(|x| (x).value).collect::<Vec<c_int>>();
+        if ts.inotes.is_some() && ts.cnotes.is_some() {
+            Err(DssError::raise("Integer and character notes cannot both be stored".to_string()))?;
+        }
+        let mut cnotes:Option<Vec<u8>> = ts.cnotes.as_ref().map(|notes| {
+            notes.iter().flat_map(|x| x.bytes().chain(std::iter::once(0u8))).collect()
+        });
+        let time_zone = match ts.time_zone {
+            Some(ref x) => Some(CString::new(x.clone()).map_err(|_| DssError::raise("Invalid time zone name".to_string()))?),
+            None => None
+        };
 
         unsafe {    
-            match &ts.ts_type {
+            let zts = match &ts.ts_type {
                 TimeSeriesType::regular => {
-                    let values = &mut ts.values;
                     let date_time = times[0].to_string().unwrap();
                     let start_date = CString::new(date_time.0).unwrap();
                     let start_time = CString::new(date_time.1).unwrap();
-                    let zts = zstructTsNewRegFloats(path.as_ptr(),values.as_mut_ptr(),count,
-                                                    start_date.as_ptr(),start_time.as_ptr(),
-                                                    unit.as_ptr(),typ.as_ptr());
-                    err = err.update();
-                    err.is_ok()?;
-                    status = ztsStore(self.ifltab.as_mut_ptr(),zts,storage_flag);
-                    err = err.update();
-                    err.is_ok()?;                   
+                    match &mut ts.values {
+                        DataValues::float(values) => zstructTsNewRegFloats(path.as_ptr(),values.as_mut_ptr(),count,
+                                                                            start_date.as_ptr(),start_time.as_ptr(),
+                                                                            unit.as_ptr(),typ.as_ptr()),
+                        DataValues::double(values) => zstructTsNewRegDoubles(path.as_ptr(),values.as_mut_ptr(),count,
+                                                                              start_date.as_ptr(),start_time.as_ptr(),
+                                                                              unit.as_ptr(),typ.as_ptr()),
+                    }
                 },
 
                 TimeSeriesType::irregular => {
-                    let mut values:Vec<f64>= ts.values().iter().map(|x| *x as f64).collect();
-                    let mut itimes = times.iter().map(|x| (x).value).collect::<Vec<c_int>>();
                     let gran_sec = times[0].granularity.value();
                     let basedate_str = CString::new(HecTime::julian_to_date(times[0].basedate_days,None).unwrap()).unwrap();
-                    let zts = zstructTsNewIrregDoubles(path.as_ptr(),values.as_mut_slice().as_mut_ptr(),count,
-                                                        itimes.as_mut_slice().as_mut_ptr(),gran_sec,basedate_str.as_ptr(),
-                                                        unit.as_ptr(),typ.as_ptr());
-                    err = err.update();
-                    err.is_ok()?;
-                    status = ztsStore(self.ifltab.as_mut_ptr(),zts,storage_flag);
-                    err = err.update();
-                    err.is_ok()?;
+                    match &mut ts.values {
+                        DataValues::float(values) => zstructTsNewIrregFloats(path.as_ptr(),values.as_mut_ptr(),count,
+                                                                              itimes.as_mut_ptr(),gran_sec,basedate_str.as_ptr(),
+                                                                              unit.as_ptr(),typ.as_ptr()),
+                        DataValues::double(values) => zstructTsNewIrregDoubles(path.as_ptr(),values.as_mut_ptr(),count,
+                                                                                itimes.as_mut_ptr(),gran_sec,basedate_str.as_ptr(),
+                                                                                unit.as_ptr(),typ.as_ptr()),
+                    }
                 }
             };
+            err = err.update();
+            err.is_ok()?;
+            if zts.is_null() {
+                Err(DssError::raise("Error occured with allocation of underlying time-series object".to_string()))?;
+            }
+            if let Some(ref mut quality) = ts.quality {
+                (*zts).quality = quality.as_mut_ptr();
+                (*zts).qualityElementSize = ts.quality_element_size;
+            }
+            if let Some(ref mut inotes) = ts.inotes {
+                (*zts).inotes = inotes.as_mut_ptr();
+                (*zts).inoteElementSize = ts.inote_element_size;
+            }
+            if let Some(ref mut cnotes) = cnotes {
+                (*zts).cnotes = cnotes.as_mut_ptr() as *mut c_char;
+                (*zts).cnotesLengthTotal = cnotes.len() as c_int;
+            }
+            if let Some(ref time_zone) = time_zone {
+                (*zts).timeZoneName = time_zone.as_ptr() as *mut c_char;
+            }
+            if let Some(precision) = ts.precision {
+                (*zts).precision = precision;
+            }
+            if let Some(ref mut header) = ts.user_header {
+                (*zts).userHeader = header.as_mut_ptr();
+                (*zts).userHeaderNumber = header.len() as c_int;
+            }
+            status = ztsStore(self.ifltab.as_mut_ptr(),zts,storage_flag);
+            zstructFree(zts as *mut c_void);
+            err = err.update();
+            err.is_ok()?;
         };
+        if status != 0 {
+            Err(DssError::raise(format!("Error status {} returned while storing time-series {:?}",status,&path)))?;
+        }
+
+        Ok(())
+    }
+
+    #[cfg_attr(feature="threadsafe",nonparallel(MUTX))]
+    pub fn read_ts_profile(&mut self,dss_path:DssPathname,as_double:Option<bool>) -> DssResult<TimeSeriesProfile> {
+        let mut err = DssError::new();
+        let path = CString::new(dss_path.to_string()).expect("error with dss pathname");
+        unsafe {
+            let zts = zstructTsNew(path.as_ptr());
+            if zts.is_null() {
+                Err(DssError::raise("Error occured with allocation of underlying time-series object".to_string()))?;
+            }
+            (*zts).boolRetrieveAllTimes = 1;
+            let float_or_double = float_or_double_flag(as_double);
+            let status = ztsRetrieve(self.ifltab.as_mut_ptr(),zts,0,float_or_double,0);
+            err = err.update();
+            if let Err(e) = err.is_ok() {
+                zstructFree(zts as *mut c_void);
+                return Err(e)
+            }
+            if status != 0 {
+                zstructFree(zts as *mut c_void);
+                Err(DssError::raise(format!("Error status {} returned while reading time-series profile {}",status,dss_path.to_string())))?;
+            }
+            let profile = TimeSeriesProfile::from_struct(zts);
+            zstructFree(zts as *mut c_void);
+            profile
+        }
+    }
+
+    #[cfg_attr(feature="threadsafe",nonparallel(MUTX))]
+    pub fn put_ts_profile(&mut self,profile:TimeSeriesProfile,mode:Option<StoreMode>) -> DssResult<()> {
+        let mut profile = profile;
+        let mut err = DssError::new();
+        let path = match &profile.pathname {
+                    Some(pathname) => CString::new(pathname.to_string()).unwrap(),
+                    _ => Err(DssError::raise("Pathname not specified".to_string()))?,
+            };
+        let mode = mode.unwrap_or(StoreMode::default(profile.ts_type));
+        let storage_flag = mode.value(profile.ts_type)?;
+        let (num_times,num_depths) = profile.shape();
+        if num_times == 0 {
+            Err(DssError::raise("TimeSeriesProfile has no data to store".to_string()))?;
+        }
+        if profile.depths.is_double() != profile.values.is_double() {
+            Err(DssError::raise("Depths and values of TimeSeriesProfile must have the same precision".to_string()))?;
+        }
+        let depth_unit = CString::new(profile.depth_unit.clone()).unwrap();
+        let value_unit = CString::new(profile.value_unit.clone()).unwrap();
+        let typ = CString::new(profile.data_type.clone()).unwrap();
+        let times = profile.times(false).expect("Times or start_time not specified for TimeSeriesProfile");
+        let mut itimes = times.iter().map(|x| x.value).collect::<Vec<c_int>>();
 
+        unsafe {
+            let zts = zstructTsNew(path.as_ptr());
+            if zts.is_null() {
+                Err(DssError::raise("Error occured with allocation of underlying time-series object".to_string()))?;
+            }
+            match profile.ts_type {
+                TimeSeriesType::regular => {
+                    let (julian,seconds) = times[0].to_julian_seconds();
+                    (*zts).startJulianDate = julian;
+                    (*zts).startTimeSeconds = seconds;
+                },
+                TimeSeriesType::irregular => {
+                    (*zts).times = itimes.as_mut_ptr();
+                    (*zts).timeGranularitySeconds = times[0].granularity.value();
+                    (*zts).julianBaseDate = times[0].basedate_days;
+                }
+            }
+            (*zts).numberValues = num_times as c_int;
+            (*zts).profileDepthsNumber = num_depths as c_int;
+            match (&mut profile.depths,&mut profile.values) {
+                (DataValues::double(depths),DataValues::double(values)) => {
+                    (*zts).doubleProfileDepths = depths.as_mut_ptr();
+                    (*zts).doubleProfileValues = values.as_mut_ptr();
+                },
+                (DataValues::float(depths),DataValues::float(values)) => {
+                    (*zts).floatProfileDepths = depths.as_mut_ptr();
+                    (*zts).floatProfileValues = values.as_mut_ptr();
+                },
+                _ => {}
+            }
+            (*zts).unitsProfileDepths = depth_unit.as_ptr() as *mut c_char;
+            (*zts).unitsProfileValues = value_unit.as_ptr() as *mut c_char;
+            (*zts).type_ = typ.as_ptr() as *mut c_char;
+            let status = ztsStore(self.ifltab.as_mut_ptr(),zts,storage_flag);
+            zstructFree(zts as *mut c_void);
+            err = err.update();
+            err.is_ok()?;
+            if status != 0 {
+                Err(DssError::raise(format!("Error status {} returned while storing time-series profile {:?}",status,&path)))?;
+            }
+        }
         Ok(())
     }
 
     #[cfg_attr(feature="threadsafe",nonparallel(MUTX))]
     pub fn read_pd(&mut self,dss_path:DssPathname,options:Option<PairedDataOptions>) -> DssResult<PairedDataTable>{
         let path = CString::new(dss_path.to_string()).expect("error with dss pathname");
+        let options = options.unwrap_or(PairedDataOptions::new());
+        let slice = &options.slice;
+        let bounds = if slice.row_start > 0 || slice.col_start > 0 || slice.row_end.is_some() || slice.col_end.is_some() {
+            let (pd_rows,pd_cols) = self.pd_record_size(&path)?;
+            Some(slice.bounds(pd_rows,pd_cols)?)
+        } else {
+            None
+        };
+        let float_or_double = float_or_double_flag(options.as_double);
+        let mut err = DssError::new();
         let zpd = unsafe {
             zstructPdNew(path.as_ptr())
         };
@@ -1000,71 +3526,265 @@ impl HecDss {
         let mut rows = 0;
         let mut cols = 0;
         unsafe {
-            match options {
-                Some(opt) => {
-                    let zrs = zstructRecordSizeNew(path.as_ptr());
-                    if zrs.is_null() {
-                        Err(DssError::raise(format!("Error occured while determining meta data of pd record: {:?}",&path)))?;
-                    }
-                    let pd_rows = (*zrs).pdNumberOrdinates;
-                    let pd_cols = (*zrs).pdNumberCurves;
-                    let row_start = opt.slice.row_start;
-                    let col_start = opt.slice.col_start;
-                    let row_end = opt.slice.row_end.unwrap_or(pd_rows);
-                    let col_end = opt.slice.col_end.unwrap_or(pd_cols);
-                    if row_start > pd_rows || row_start <1 {
-                        Err(DssError::raise(format!("Paired Data row start index {} not in the range 1 - {}",row_start,pd_rows)))?;
-                    }
-                    if row_end > pd_rows || row_end <1 {
-                        Err(DssError::raise(format!("Paired Data row end index {} not in the range 1 - {}",row_end,pd_rows)))?;
-                    }
-                    if col_start > pd_cols || col_start < 1  {
-                        Err(DssError::raise(format!("Paired Data column start index {} not in the range 1 - {}",col_start,pd_cols)))?;
-                    }
-                    if col_end > pd_cols || col_end < 1  {
-                        Err(DssError::raise(format!("Paired Data column end index {} not in the range 1 - {}",col_end,pd_cols)))?;
-                    }
-                    (*zpd).startingOrdinate = row_start;
-                    (*zpd).endingOrdinate = row_end;
-                    (*zpd).startingCurve = col_start;
-                    (*zpd).endingCurve = col_start;
-                },
-                None => {}
+            if let Some((row_start,row_end,col_start,col_end)) = bounds {
+                (*zpd).startingOrdinate = row_start;
+                (*zpd).endingOrdinate = row_end;
+                (*zpd).startingCurve = col_start;
+                (*zpd).endingCurve = col_end;
+            }
+            let status = zpdRetrieve(self.ifltab.as_mut_ptr(),zpd,float_or_double);
+            err = err.update();
+            if let Err(e) = err.is_ok() {
+                zstructFree(zpd as *mut c_void);
+                return Err(e)
+            }
+            if status != 0 {
+                zstructFree(zpd as *mut c_void);
+                Err(DssError::raise(format!("Error status {} returned while reading paired data {:?}",status,&path)))?;
             }
-            let status = zpdRetrieve(self.ifltab.as_mut_ptr(),zpd,1);
-            rows = (*zpd).numberOrdinates;
-            cols = (*zpd).numberCurves;
+            rows = if (*zpd).numberOrdinatesInStruct > 0 {(*zpd).numberOrdinatesInStruct} else {(*zpd).numberOrdinates};
+            cols = if (*zpd).numberCurvesInStruct > 0 {(*zpd).numberCurvesInStruct} else {(*zpd).numberCurves};
         }
         if rows < 1 || cols < 1 {
+            unsafe {zstructFree(zpd as *mut c_void)};
             Err(DssError::raise(format!("Paired Data has invalid number of rows {} or columns {}",rows,cols)))?;
         }
-        let mut ptable = PairedDataTable::new(rows,cols);
+        let size = (rows as usize)*(cols as usize);
+        let mut ptable = unsafe {
+            if !(*zpd).doubleOrdinates.is_null() && !(*zpd).doubleValues.is_null() {
+                let mut ptable = PairedDataTable::new_double(rows,cols);
+                ptable.set_double_index(std::slice::from_raw_parts((*zpd).doubleOrdinates,rows as usize))?;
+                ptable.set_double_columns(std::slice::from_raw_parts((*zpd).doubleValues,size))?;
+                ptable
+            } else {
+                let mut ptable = PairedDataTable::new(rows,cols);
+                ptable.set_index(std::slice::from_raw_parts((*zpd).floatOrdinates,rows as usize))?;
+                ptable.set_columns(std::slice::from_raw_parts((*zpd).floatValues,size))?;
+                ptable
+            }
+        };
         ptable.set_pathname(dss_path);
         unsafe {
-            let buf_ptr:*const f32 = (*zpd).floatOrdinates;
-            ptable.set_index(std::slice::from_raw_parts(buf_ptr, rows as usize));
-            let buf_ptr = (*zpd).floatValues;
-            ptable.set_columns(std::slice::from_raw_parts(buf_ptr, (rows as usize)*(cols as usize)));
-            let label_len:i32 = (*zpd).labelsLength;
-            let clabels = std::slice::from_raw_parts((*zpd).labels, label_len as usize);
-            let labels = mem::transmute::<&[i8],&[u8]>(clabels);
-            let headers = str::from_utf8(labels).unwrap();
-            let mut headers:Vec<&str> = headers.trim_end_matches("\x00").split("\x00").collect();
-            if headers.len() != (cols as usize) {
-                let mut headers_fixed:Vec<&str> = Vec::new();
-                let count = std::cmp::min(headers.len(),cols as usize);
-                for i in 0..count {
-                    headers_fixed.push(headers[i]);
-                }
-                ptable.set_headers(Some(headers_fixed))?;
+            let cstring = |ptr:*const c_char| if ptr.is_null() {
+                String::new()
             } else {
-                ptable.set_headers(Some(headers))?;
+                CStr::from_ptr(ptr).to_string_lossy().trim().to_string()
+            };
+            ptable.set_index_unit(&cstring((*zpd).unitsIndependent));
+            ptable.set_index_type(&cstring((*zpd).typeIndependent));
+            ptable.set_column_unit(&cstring((*zpd).unitsDependent));
+            ptable.set_column_type(&cstring((*zpd).typeDependent));
+            ptable.set_index_is_xaxis((*zpd).boolIndependentIsXaxis != 0);
+            if (*zpd).xprecision >= 0 || (*zpd).yprecision >= 0 {
+                ptable.set_precision(Some(((*zpd).xprecision,(*zpd).yprecision)));
+            }
+            let time_zone = cstring((*zpd).timeZoneName);
+            if !time_zone.is_empty() {
+                ptable.set_time_zone(Some(&time_zone));
+            }
+            let label_len:i32 = (*zpd).labelsLength;
+            if !(*zpd).labels.is_null() && label_len > 0 {
+                let clabels = std::slice::from_raw_parts((*zpd).labels, label_len as usize);
+                let labels = mem::transmute::<&[i8],&[u8]>(clabels);
+                let headers = String::from_utf8_lossy(labels);
+                // one label per column, missing labels are left empty
+                let mut headers:Vec<&str> = headers.trim_end_matches("\x00").split("\x00").collect();
+                headers.resize(cols as usize,"");
+                ptable.set_headers(Some(&headers))?;
             }
             zstructFree(zpd as *mut c_void);
         }
         Ok(ptable)
     }
-    
+
+    fn pd_record_size(&mut self,path:&CStr) -> DssResult<(c_int,c_int)> {
+        unsafe {
+            let zrs = zstructRecordSizeNew(path.as_ptr());
+            if zrs.is_null() {
+                Err(DssError::raise(format!("Error occured while determining meta data of pd record: {:?}",path)))?;
+            }
+            let status = zgetRecordSize(self.ifltab.as_mut_ptr(),zrs);
+            let size = ((*zrs).pdNumberOrdinates,(*zrs).pdNumberCurves);
+            zstructFree(zrs as *mut c_void);
+            if status != 0 {
+                Err(DssError::raise_kind(DssErrorKind::RECORD_DOES_NOT_EXIST,
+                                         format!("Paired data record {:?} does not exist",path)))?;
+            }
+            Ok(size)
+        }
+    }
+
+    #[cfg_attr(feature="threadsafe",nonparallel(MUTX))]
+    pub fn put_pd(&mut self,table:PairedDataTable) -> DssResult<()> {
+        let mut err = DssError::new();
+        let path = match table.pathname {
+                    Some(ref pathname) => CString::new(pathname.to_string()).map_err(|e| DssError::raise(e.to_string()))?,
+                    _ => Err(DssError::raise("Pathname not specified".to_string()))?,
+            };
+        let (rows,cols) = table.shape;
+        if rows < 1 || cols < 1 {
+            Err(DssError::raise(format!("Paired Data has invalid number of rows {} or columns {}",rows,cols)))?;
+        }
+        table.check_precision()?;
+        let index_unit = CString::new(table.index_unit.clone()).map_err(|e| DssError::raise(e.to_string()))?;
+        let index_type = CString::new(table.index_type.clone()).map_err(|e| DssError::raise(e.to_string()))?;
+        let column_unit = CString::new(table.column_unit.clone()).map_err(|e| DssError::raise(e.to_string()))?;
+        let column_type = CString::new(table.column_type.clone()).map_err(|e| DssError::raise(e.to_string()))?;
+        let time_zone = match table.time_zone {
+            Some(ref x) => Some(CString::new(x.clone()).map_err(|e| DssError::raise(e.to_string()))?),
+            None => None
+        };
+        // zpd keeps pointers to these buffers, so they must outlive the store call
+        let mut index = table.index.clone();
+        let mut values = table.values_concat();
+        let mut labels = table.labels_buffer()?;
+        unsafe {
+            let zpd = match (&mut index,&mut values) {
+                (DataValues::double(index),DataValues::double(values)) => {
+                    zstructPdNewDoubles(path.as_ptr(),index.as_mut_ptr(),values.as_mut_ptr(),rows,cols,
+                                        index_unit.as_ptr(),index_type.as_ptr(),
+                                        column_unit.as_ptr(),column_type.as_ptr())
+                },
+                (DataValues::float(index),DataValues::float(values)) => {
+                    zstructPdNewFloats(path.as_ptr(),index.as_mut_ptr(),values.as_mut_ptr(),rows,cols,
+                                       index_unit.as_ptr(),index_type.as_ptr(),
+                                       column_unit.as_ptr(),column_type.as_ptr())
+                },
+                _ => std::ptr::null_mut()
+            };
+            if zpd.is_null() {
+                Err(DssError::raise("Error occured with allocation of underlying paired data object".to_string()))?;
+            }
+            if let Some(ref mut labels) = labels {
+                (*zpd).labels = labels.as_mut_ptr() as *mut c_char;
+                (*zpd).labelsLength = labels.len() as c_int;
+            }
+            (*zpd).boolIndependentIsXaxis = table.index_is_xaxis as c_int;
+            if let Some((xprecision,yprecision)) = table.precision {
+                (*zpd).xprecision = xprecision;
+                (*zpd).yprecision = yprecision;
+            }
+            if let Some(ref time_zone) = time_zone {
+                (*zpd).timeZoneName = time_zone.as_ptr() as *mut c_char;
+            }
+            let status = zpdStore(self.ifltab.as_mut_ptr(),zpd,0);
+            zstructFree(zpd as *mut c_void);
+            err = err.update();
+            err.is_ok()?;
+            if status != 0 {
+                Err(DssError::raise(format!("Error status {} returned while storing paired data {:?}",status,&path)))?;
+            }
+        }
+        Ok(())
+    }
+
+    // Pre-allocates a paired data record for the shape of table, storing its index, labels and units.
+    // The curves are then written piece by piece with update_pd_curves.
+    #[cfg_attr(feature="threadsafe",nonparallel(MUTX))]
+    pub fn allocate_pd(&mut self,table:PairedDataTable) -> DssResult<()> {
+        let mut err = DssError::new();
+        let path = match table.pathname {
+                    Some(ref pathname) => CString::new(pathname.to_string()).map_err(|e| DssError::raise(e.to_string()))?,
+                    _ => Err(DssError::raise("Pathname not specified".to_string()))?,
+            };
+        let (rows,cols) = table.shape;
+        if rows < 1 || cols < 1 {
+            Err(DssError::raise(format!("Paired Data has invalid number of rows {} or columns {}",rows,cols)))?;
+        }
+        table.check_precision()?;
+        let index_unit = CString::new(table.index_unit.clone()).map_err(|e| DssError::raise(e.to_string()))?;
+        let index_type = CString::new(table.index_type.clone()).map_err(|e| DssError::raise(e.to_string()))?;
+        let column_unit = CString::new(table.column_unit.clone()).map_err(|e| DssError::raise(e.to_string()))?;
+        let column_type = CString::new(table.column_type.clone()).map_err(|e| DssError::raise(e.to_string()))?;
+        let time_zone = match table.time_zone {
+            Some(ref x) => Some(CString::new(x.clone()).map_err(|e| DssError::raise(e.to_string()))?),
+            None => None
+        };
+        let labels = table.labels_buffer()?;
+        let (xprecision,yprecision) = table.precision.unwrap_or((-1,-1));
+        // ordinates followed by the curves
+        let mut values = match (&table.index,table.values_concat()) {
+            (DataValues::double(index),DataValues::double(values)) => DataValues::double([index.clone(),values].concat()),
+            (DataValues::float(index),DataValues::float(values)) => DataValues::float([index.clone(),values].concat()),
+            _ => unreachable!()
+        };
+        let (values_ptr,value_size) = match values {
+            DataValues::float(ref mut x) => (x.as_mut_ptr() as *mut c_int,1),
+            DataValues::double(ref mut x) => (x.as_mut_ptr() as *mut c_int,2)
+        };
+        unsafe {
+            // storageFlag 10 allocates space for all curves
+            let status = zpdStoreFull(self.ifltab.as_mut_ptr(),path.as_ptr(),
+                                      values_ptr,value_size,cols,rows,
+                                      labels.as_ref().map_or(std::ptr::null(),|x| x.as_ptr() as *const c_char),
+                                      labels.is_some() as c_int,
+                                      index_unit.as_ptr(),index_type.as_ptr(),
+                                      column_unit.as_ptr(),column_type.as_ptr(),
+                                      table.index_is_xaxis as c_int,
+                                      std::ptr::null_mut(),0,
+                                      std::ptr::null_mut(),0,
+                                      std::ptr::null_mut(),0,
+                                      time_zone.as_ref().map_or(std::ptr::null(),|x| x.as_ptr()),0,
+                                      std::ptr::null_mut(),0,
+                                      xprecision,yprecision,10);
+            err = err.update();
+            err.is_ok()?;
+            if status != 0 {
+                Err(DssError::raise(format!("Error status {} returned while allocating paired data {:?}",status,&path)))?;
+            }
+        }
+        Ok(())
+    }
+
+    // Overwrites the curves and ordinates selected by slice (1 based, inclusive) of an existing record.
+    // values holds the selected part of each curve, one curve after another.
+    #[cfg_attr(feature="threadsafe",nonparallel(MUTX))]
+    pub fn update_pd_curves(&mut self,dss_path:&DssPathname,slice:PairedDataSlice,values:&DataValues) -> DssResult<()> {
+        let mut err = DssError::new();
+        let path = CString::new(dss_path.to_string()).map_err(|e| DssError::raise(e.to_string()))?;
+        let (pd_rows,pd_cols) = self.pd_record_size(&path)?;
+        let (row_start,row_end,col_start,col_end) = slice.bounds(pd_rows,pd_cols)?;
+        let count = ((row_end-row_start+1)*(col_end-col_start+1)) as usize;
+        if values.len() != count {
+            Err(DssError::raise(format!("Expected {} values for the selected curves, found {}",count,values.len())))?;
+        }
+        // curves are written into the stored array as is, so the precision has to match
+        let stored_double = match RecordType::from_value(unsafe {zdataType(self.ifltab.as_mut_ptr(),path.as_ptr())}) {
+            RecordType::pd => false,
+            RecordType::pdd => true,
+            x => Err(DssError::raise(format!("Record {} is not paired data, record type {:?}",dss_path.to_string(),x)))?
+        };
+        if values.is_double() != stored_double {
+            Err(DssError::raise(format!("Values are {} but paired data {} is stored as {}",
+                                        if values.is_double() {"double"} else {"float"},dss_path.to_string(),
+                                        if stored_double {"double"} else {"float"})))?;
+        }
+        let mut values = values.clone();
+        unsafe {
+            let zpd = zstructPdNew(path.as_ptr());
+            if zpd.is_null() {
+                Err(DssError::raise("Error occured with allocation of underlying paired data object".to_string()))?;
+            }
+            (*zpd).numberOrdinates = pd_rows;
+            (*zpd).numberCurves = pd_cols;
+            (*zpd).startingOrdinate = row_start;
+            (*zpd).endingOrdinate = row_end;
+            (*zpd).startingCurve = col_start;
+            (*zpd).endingCurve = col_end;
+            match values {
+                DataValues::float(ref mut x) => (*zpd).floatValues = x.as_mut_ptr(),
+                DataValues::double(ref mut x) => (*zpd).doubleValues = x.as_mut_ptr()
+            }
+            let status = zpdStore(self.ifltab.as_mut_ptr(),zpd,0);
+            zstructFree(zpd as *mut c_void);
+            err = err.update();
+            err.is_ok()?;
+            if status != 0 {
+                Err(DssError::raise(format!("Error status {} returned while updating paired data {:?}",status,&path)))?;
+            }
+        }
+        Ok(())
+    }
 
     #[cfg_attr(feature="threadsafe",nonparallel(MUTX))]
     pub fn read_grid(&mut self) {
@@ -1072,12 +3792,75 @@ impl HecDss {
     }
 }
 
+impl <'a> Iterator for TimeSeriesIter<'a> {
+    type Item = DssResult<Vec<(HecTime,f64)>>;
+
+    #[cfg_attr(feature="threadsafe",nonparallel(MUTX))]
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.done {
+            // read one block (D-part), clipped to the requested window
+            let next_block = unsafe {ztsIncrementBlock(self.block_julian,self.block_size)};
+            let start = std::cmp::max(self.start,(self.block_julian,1));
+            let end = std::cmp::min(self.end,(next_block-1,86400));
+            self.block_julian = next_block;
+            self.done = end == self.end;
+            if start > end {
+                continue
+            }
+            let mut err = DssError::new();
+            let tsc = unsafe {
+                let zts = zstructTsNew(self.pathname.as_ptr());
+                if zts.is_null() {
+                    return Some(Err(DssError::raise("Error occured with allocation of underlying time-series object".to_string())))
+                }
+                (*zts).startJulianDate = start.0;
+                (*zts).startTimeSeconds = start.1;
+                (*zts).endJulianDate = end.0;
+                (*zts).endTimeSeconds = end.1;
+                let status = ztsRetrieve(self.dss.ifltab.as_mut_ptr(),zts,0,2,0);
+                err = err.update();
+                let tsc = match err.is_ok() {
+                    Ok(_) if status != 0 => Err(DssError::raise(format!("Error status {} returned while reading time-series {:?}",status,&self.pathname))),
+                    Ok(_) => TimeSeriesContainer::from_struct(zts),
+                    Err(e) => Err(e)
+                };
+                zstructFree(zts as *mut c_void);
+                tsc
+            };
+            let tsc = match tsc {
+                Ok(x) => x,
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(e))
+                }
+            };
+            if tsc.len() == 0 {
+                continue
+            }
+            let times = tsc.times(true).unwrap_or_default();
+            let chunk:Vec<(HecTime,f64)> = times.into_iter().zip(tsc.values().to_double()).collect();
+            return Some(Ok(chunk))
+        }
+        None
+    }
+}
+
+impl Drop for ChangeTracker {
+    fn drop(&mut self) {
+        unsafe {
+            zstructFree(self.baseline as *mut c_void);
+        }
+    }
+}
+
 impl Drop for HecDss {
     #[cfg_attr(feature="threadsafe",nonparallel(MUTX))]
     fn drop(&mut self) {
         println!("Freeing the HecDss resource for linked with file: {}",self.filename);
-        unsafe {
-            zclose(self.ifltab.as_mut_slice().as_mut_ptr());
+        if self.open {
+            unsafe {
+                zclose(self.ifltab.as_mut_slice().as_mut_ptr());
+            }
         }
     }
 }
@@ -1102,6 +3885,36 @@ mod tests {
         println!("Hectime to string = {:?}",datetime);
     }
 
+    #[test]
+    fn quality_flag_bits() {
+        // screened, questionable, range 2, changed, manual replacement, explicit method
+        let flag = QualityFlag::new(1 | 8 | (2 << 5) | 128 | (3 << 8) | (2 << 11));
+        assert!(flag.is_screened());
+        assert_eq!(flag.validity(),QualityValidity::questionable);
+        assert_eq!(flag.range(),2);
+        assert!(flag.is_changed());
+        assert_eq!(flag.replacement_cause(),QualityReplacementCause::manual);
+        assert_eq!(flag.replacement_method(),QualityReplacementMethod::explicit);
+        assert!(!flag.is_protected());
+        assert_eq!(QualityFlag::new(0).validity(),QualityValidity::unknown);
+    }
+
+    #[test]
+    fn store_mode_flags() {
+        assert_eq!(StoreMode::replace_missing_only.value(TimeSeriesType::regular).unwrap(),1);
+        assert_eq!(StoreMode::replace_window.value(TimeSeriesType::irregular).unwrap(),1);
+        assert!(StoreMode::merge.value(TimeSeriesType::regular).is_err());
+        assert!(StoreMode::write_all_missing.value(TimeSeriesType::irregular).is_err());
+    }
+
+    #[test]
+    fn time_interval_from_epart() {
+        assert_eq!(HecTimeInterval::from_string("15Minute"),Some(HecTimeInterval::minute(15)));
+        assert_eq!(HecTimeInterval::from_string("1Month"),Some(HecTimeInterval::month));
+        assert_eq!(HecTimeInterval::from_string("Semi-Month"),Some(HecTimeInterval::semi_month));
+        assert_eq!(HecTimeInterval::from_string("IR-Month"),None);
+    }
+
     #[test]
     fn read_regular_timeseries() {
         let file_path = String::from("data/example.dss");
@@ -1117,7 +3930,7 @@ mod tests {
                 let dtype = tsc.dtype();
 
                 assert_eq!(values.len(),12);
-                assert_eq!(values[0],450.0);
+                assert_eq!(values.to_double()[0],450.0);
                 println!("Total data = {}",values.len());
                 println!("Read data values = {:?}",values);
                 println!("Data unit = {:?}, type = {:?}",unit,dtype);
@@ -1194,6 +4007,548 @@ mod tests {
         };
     }
 
+    #[test]
+    fn write_read_regular_double_timeseries() {
+        let file_path = String::from("data/example.dss");
+        let dss_path = DssPathname::from_string("/REGULAR/TIMESERIES/STAGE//1Hour/WriteDouble/");
+        let mut fid = HecDss::new(file_path).expect("Failed to open HEC-DSS file!");
+        let mut tsc = TimeSeriesContainer::new_double(TimeSeriesType::regular,4);
+        let start_date = HecTime::from_string("01MAY2023:2400",None,None).expect("Error converting date string to HecTime");
+        let values:[f64;4] = [1012.123456789,1012.223456789,1012.323456789,1012.423456789];
+        tsc.set_pathname(dss_path.clone());
+        tsc.set_unit("feet");
+        tsc.set_type("INST-VAL");
+        tsc.set_double_values(&values).unwrap();
+        tsc.set_times(&[start_date]).unwrap();
+        fid.put_ts(tsc,None).expect("Fail to write regular double time-series to dss");
+        let tsc = fid.read_ts(dss_path.unwrap(),Some(0),Some(true),Some(true)).expect("Fail to read regular double time-series");
+        assert!(tsc.is_double());
+        assert_eq!(tsc.values().as_double().unwrap(),&values);
+    }
+
+    #[test]
+    fn write_read_timeseries_quality() {
+        let file_path = String::from("data/example.dss");
+        let dss_path = DssPathname::from_string("/REGULAR/TIMESERIES/FLOW//1Hour/WriteQuality/");
+        let mut fid = HecDss::new(file_path).expect("Failed to open HEC-DSS file!");
+        let mut tsc = TimeSeriesContainer::new(TimeSeriesType::regular,3);
+        let start_date = HecTime::from_string("01MAY2023:2400",None,None).expect("Error converting date string to HecTime");
+        // screened okay, screened questionable, screened rejected and protected
+        let flags = [QualityFlag::new(1 | 2),QualityFlag::new(1 | 8),QualityFlag::new(1 | 16 | (1 << 31))];
+        tsc.set_pathname(dss_path.clone());
+        tsc.set_unit("cfs");
+        tsc.set_type("INST-VAL");
+        tsc.set_values(&[10.0,20.0,30.0]).unwrap();
+        tsc.set_times(&[start_date]).unwrap();
+        tsc.set_quality(Some(&flags)).unwrap();
+        fid.put_ts(tsc,None).expect("Fail to write regular time-series with quality to dss");
+        let tsc = fid.read_ts(dss_path.unwrap(),Some(0),None,Some(true)).expect("Fail to read regular time-series with quality");
+        assert_eq!(tsc.quality_element_size(),1);
+        assert_eq!(tsc.quality().unwrap(),flags.to_vec());
+        assert_eq!(tsc.quality().unwrap()[1].validity(),QualityValidity::questionable);
+        assert!(tsc.quality().unwrap()[2].is_protected());
+    }
+
+    #[test]
+    fn write_read_timeseries_notes_and_header() {
+        let file_path = String::from("data/example.dss");
+        let dss_path = DssPathname::from_string("/REGULAR/TIMESERIES/FLOW//1Hour/WriteNotes/");
+        let mut fid = HecDss::new(file_path).expect("Failed to open HEC-DSS file!");
+        let mut tsc = TimeSeriesContainer::new(TimeSeriesType::regular,3);
+        let start_date = HecTime::from_string("01MAY2023:2400",None,None).expect("Error converting date string to HecTime");
+        let notes = ["gage read by observer","","ice affected"];
+        tsc.set_pathname(dss_path.clone());
+        tsc.set_unit("cfs");
+        tsc.set_type("INST-VAL");
+        tsc.set_values(&[10.0,20.0,30.0]).unwrap();
+        tsc.set_times(&[start_date]).unwrap();
+        tsc.set_char_notes(Some(&notes)).unwrap();
+        tsc.set_precision(Some(2));
+        tsc.set_user_header(Some(&[1,2,3]));
+        fid.put_ts(tsc,None).expect("Fail to write regular time-series with notes to dss");
+        let tsc = fid.read_ts(dss_path.unwrap(),Some(0),None,Some(true)).expect("Fail to read regular time-series with notes");
+        assert_eq!(tsc.char_notes().unwrap(),&notes);
+        assert_eq!(tsc.precision(),Some(2));
+        assert_eq!(tsc.user_header().unwrap(),&[1,2,3]);
+    }
+
+    #[test]
+    fn write_read_timeseries_int_notes() {
+        let file_path = String::from("data/example.dss");
+        let dss_path = DssPathname::from_string("/REGULAR/TIMESERIES/FLOW//1Hour/WriteIntNotes/");
+        let mut fid = HecDss::new(file_path).expect("Failed to open HEC-DSS file!");
+        let mut tsc = TimeSeriesContainer::new(TimeSeriesType::regular,3);
+        let start_date = HecTime::from_string("01MAY2023:2400",None,None).expect("Error converting date string to HecTime");
+        let notes = [1,10,2,20,3,30];
+        tsc.set_pathname(dss_path.clone());
+        tsc.set_unit("cfs");
+        tsc.set_type("INST-VAL");
+        tsc.set_values(&[10.0,20.0,30.0]).unwrap();
+        tsc.set_times(&[start_date]).unwrap();
+        assert!(tsc.set_int_notes(Some(&notes[..5]),2).is_err());
+        tsc.set_int_notes(Some(&notes),2).unwrap();
+        fid.put_ts(tsc,None).expect("Fail to write regular time-series with integer notes to dss");
+        let tsc = fid.read_ts(dss_path.unwrap(),Some(0),None,Some(true)).expect("Fail to read regular time-series with integer notes");
+        assert_eq!(tsc.int_note_element_size(),2);
+        assert_eq!(tsc.int_notes().unwrap(),&notes);
+        assert!(tsc.char_notes().is_none());
+    }
+
+    #[test]
+    fn read_regular_timeseries_window() {
+        let file_path = String::from("data/example.dss");
+        let dss_path = String::from("/REGULAR/TIMESERIES/FLOW//1Hour/Ex1a/");
+        let mut fid = HecDss::new(file_path).expect("Failed to open HEC-DSS file!");
+        let tsc = fid.read_ts(DssPathname::from_string(&dss_path).unwrap(),None,None,Some(true)).expect("Fail to read regular time-series");
+        let times = tsc.times(true).unwrap();
+        let values = tsc.values().to_double();
+        let mut options = TimeSeriesOptions::new();
+        options.set_slice(TimeSeriesSlice::new(Some(times[2]),Some(times[5])));
+        let window = fid.read_ts_window(DssPathname::from_string(&dss_path).unwrap(),options,None).expect("Fail to read time window");
+        assert_eq!(window.len(),4);
+        assert_eq!(window.values().to_double()[0],values[2]);
+    }
+
+    #[test]
+    fn write_read_irregular_profile() {
+        let file_path = String::from("data/example.dss");
+        let dss_path = DssPathname::from_string("/PROFILE/RESERVOIR/DEPTH-TEMP//IR-MONTH/WriteProfile/");
+        let mut fid = HecDss::new(file_path).expect("Failed to open HEC-DSS file!");
+        let mut profile = TimeSeriesProfile::new(TimeSeriesType::irregular,2,3);
+        let time1 = HecTime::from_string("01MAY2023:0800",None,None).unwrap();
+        let time2 = HecTime::from_string("02MAY2023:0800",None,None).unwrap();
+        let values:[f32;6] = [20.5,18.0,12.25,21.0,18.5,12.5];
+        profile.set_pathname(dss_path.clone());
+        profile.set_depth_unit("feet");
+        profile.set_value_unit("deg C");
+        profile.set_type("INST-VAL");
+        profile.set_depths(&[0.0,10.0,50.0]).unwrap();
+        profile.set_values(&values).unwrap();
+        profile.set_times(&[time1,time2]).unwrap();
+        fid.put_ts_profile(profile,None).expect("Fail to write irregular profile to dss");
+        let profile = fid.read_ts_profile(dss_path.unwrap(),None).expect("Fail to read irregular profile");
+        assert_eq!(profile.shape(),(2,3));
+        assert_eq!(profile.values().as_float().unwrap(),&values);
+        assert_eq!(profile.profile(1).unwrap(),vec![21.0,18.5,12.5]);
+    }
+
+    #[test]
+    fn write_read_regular_double_profile() {
+        let file_path = String::from("data/example.dss");
+        let dss_path = DssPathname::from_string("/PROFILE/RESERVOIR/DEPTH-TEMP//1Day/WriteProfileDouble/");
+        let mut fid = HecDss::new(file_path).expect("Failed to open HEC-DSS file!");
+        let mut profile = TimeSeriesProfile::new_double(TimeSeriesType::regular,2,3);
+        let start = HecTime::from_string("01MAY2023:2400",None,None).unwrap();
+        let values:[f64;6] = [20.123456789,18.0,12.25,21.0,18.5,12.987654321];
+        profile.set_pathname(dss_path.clone());
+        assert_eq!(profile.interval(),Some(HecTimeInterval::day(1)));
+        profile.set_depth_unit("feet");
+        profile.set_value_unit("deg C");
+        profile.set_type("INST-VAL");
+        profile.set_double_depths(&[0.0,10.0,50.0]).unwrap();
+        profile.set_double_values(&values).unwrap();
+        profile.set_times(&[start]).unwrap();
+        let times = profile.times(true).unwrap();
+        let (julian,seconds) = times[0].to_julian_seconds();
+        assert_eq!(times[1].to_julian_seconds(),(julian+1,seconds));
+        fid.put_ts_profile(profile,None).expect("Fail to write regular profile to dss");
+        let profile = fid.read_ts_profile(dss_path.unwrap(),None).expect("Fail to read regular profile");
+        assert!(profile.is_double());
+        assert_eq!(profile.shape(),(2,3));
+        assert_eq!(profile.values().as_double().unwrap(),&values);
+        let times = profile.times(true).unwrap();
+        assert_eq!(times[0].to_julian_seconds(),(julian,seconds));
+        assert_eq!(times[1].to_julian_seconds(),(julian+1,seconds));
+    }
+
+    #[test]
+    fn write_irregular_timeseries_block() {
+        let file_path = String::from("data/example.dss");
+        let mut fid = HecDss::new(file_path).expect("Failed to open HEC-DSS file!");
+        let times = [HecTime::from_string("01MAY2023:0815",None,None).unwrap(),
+                     HecTime::from_string("03MAY2023:1730",None,None).unwrap()];
+        let mut tsc = TimeSeriesContainer::new(TimeSeriesType::irregular,2);
+        tsc.set_pathname(DssPathname::from_string("/IRREGULAR/TIMESERIES/STAGE//IR-Month/WriteBlock/"));
+        tsc.set_unit("feet");
+        tsc.set_type("INST-VAL");
+        tsc.set_values(&[5.5,6.25]).unwrap();
+        tsc.set_times(&times).unwrap();
+        assert!(fid.put_ts_irregular(tsc.clone(),HecTimeIntervalIreg::year,StoreMode::merge).is_err());
+        assert!(fid.put_ts_irregular(tsc.clone(),HecTimeIntervalIreg::month,StoreMode::replace_all).is_err());
+        fid.put_ts_irregular(tsc,HecTimeIntervalIreg::month,StoreMode::replace_window).expect("Fail to write irregular time-series to dss");
+        let tsc = fid.read_ts(DssPathname::from_string("/IRREGULAR/TIMESERIES/STAGE//IR-Month/WriteBlock/").unwrap(),None,None,Some(true))
+                     .expect("Fail to read irregular time-series");
+        assert_eq!(tsc.values().to_double(),vec![5.5,6.25]);
+    }
+
+    #[test]
+    fn regular_timeseries_time_offset() {
+        let file_path = String::from("data/example.dss");
+        let dss_path = DssPathname::from_string("/REGULAR/TIMESERIES/FLOW//6Hour/Offset/");
+        let mut fid = HecDss::new(file_path).expect("Failed to open HEC-DSS file!");
+        let mut tsc = TimeSeriesContainer::new(TimeSeriesType::regular,2);
+        let start_date = HecTime::from_string("01MAY2023:1200",None,None).unwrap();
+        tsc.set_pathname(dss_path.clone());
+        assert_eq!(tsc.interval(),Some(HecTimeInterval::hour(6)));
+        tsc.set_unit("cfs");
+        tsc.set_type("INST-VAL");
+        tsc.set_values(&[1.0,2.0]).unwrap();
+        tsc.set_times(&[start_date]).unwrap();
+        tsc.set_time_offset(3600);
+        tsc.set_time_zone(Some("UTC"));
+        let times:Vec<(String,String)> = tsc.times(true).unwrap().iter().map(|x| x.to_string().unwrap()).collect();
+        assert_eq!(times[0].1,"1300");
+        assert_eq!(times[1].1,"1900");
+        fid.put_ts(tsc,None).expect("Fail to write offset time-series to dss");
+
+        let tsc = fid.read_ts(dss_path.unwrap(),None,None,Some(true)).expect("Fail to read offset time-series");
+        assert_eq!(tsc.time_zone(),Some("UTC"));
+        assert_eq!(tsc.time_offset(),3600);
+        let read_times:Vec<(String,String)> = tsc.times(true).unwrap().iter().map(|x| x.to_string().unwrap()).collect();
+        assert_eq!(read_times,times);
+        assert_eq!(tsc.values().to_double(),vec![1.0,2.0]);
+    }
+
+    #[test]
+    fn iterate_regular_timeseries() {
+        let file_path = String::from("data/example.dss");
+        let dss_path = String::from("/REGULAR/TIMESERIES/FLOW//1Hour/Ex1a/");
+        let mut fid = HecDss::new(file_path).expect("Failed to open HEC-DSS file!");
+        let tsc = fid.read_ts(DssPathname::from_string(&dss_path).unwrap(),None,None,Some(true)).expect("Fail to read regular time-series");
+        let times = tsc.times(true).unwrap();
+        let values = tsc.values().to_double();
+        let iter = fid.iter_ts(DssPathname::from_string(&dss_path).unwrap(),times[0],times[times.len()-1]).expect("Fail to iterate time-series");
+        let mut read = Vec::new();
+        for chunk in iter {
+            read.extend(chunk.expect("Fail to read time-series block").into_iter().map(|(_,x)| x));
+        }
+        assert_eq!(read,values);
+    }
+
+    #[test]
+    fn catalog_pathnames() {
+        let file_path = String::from("data/example.dss");
+        let mut fid = HecDss::new(file_path).expect("Failed to open HEC-DSS file!");
+        let mut options = CatalogOptions::new();
+        options.set_include_dates(true);
+        let entries = fid.catalog(Some(options)).expect("Fail to catalog dss file");
+        let entry = entries.iter().find(|x| x.pathname().to_string().starts_with("/REGULAR/TIMESERIES/FLOW/"))
+                           .expect("Regular time-series missing from catalog");
+        assert!(entry.record_type().is_time_series());
+        assert!(entry.dates().is_some());
+        assert!(entries.iter().any(|x| x.record_type().is_paired_data()));
+    }
+
+    #[test]
+    fn pathname_pattern_matching() {
+        let pattern = PathnamePattern::from_string("/*/BRIDGEPORT/FLOW*/*/1Hour/*/").unwrap();
+        let path = DssPathname::from_string("/HOUSATONIC/BRIDGEPORT/FLOW-OBS/01Jan2000/1Hour/Obs/").unwrap();
+        assert!(pattern.matches(&path));
+        let path = DssPathname::from_string("/HOUSATONIC/BRIDGEPORT/STAGE/01Jan2000/1Hour/Obs/").unwrap();
+        assert!(!pattern.matches(&path));
+        let pattern = PathnamePattern::from_string("/*/*/F*W/*/*/*/").unwrap();
+        assert!(pattern.matches(&DssPathname::from_string("/A/B/FLOW/D/E/F/").unwrap()));
+        assert!(!pattern.matches(&DssPathname::from_string("/A/B/FLOWS/D/E/F/").unwrap()));
+    }
+
+    #[test]
+    fn catalog_with_filter() {
+        let file_path = String::from("data/example.dss");
+        let mut fid = HecDss::new(file_path).expect("Failed to open HEC-DSS file!");
+        let mut options = CatalogOptions::new();
+        options.set_filter(PathnamePattern::from_string("/REGULAR/*/FLOW/*/*/*/").unwrap());
+        let entries = fid.catalog(Some(options)).expect("Fail to catalog dss file");
+        assert!(!entries.is_empty());
+        assert!(entries.iter().all(|x| x.pathname().to_string().starts_with("/REGULAR/")));
+    }
+
+    #[test]
+    fn condensed_catalog_read() {
+        let file_path = String::from("data/example.dss");
+        let mut fid = HecDss::new(file_path).expect("Failed to open HEC-DSS file!");
+        let condensed = fid.condensed_catalog(None).expect("Fail to catalog dss file");
+        let series = condensed.iter().find(|x| x.pathname().to_string() == "/REGULAR/TIMESERIES/FLOW//1Hour/Ex1a/")
+                              .expect("Regular time-series missing from condensed catalog").clone();
+        assert!(series.block_count() >= 1);
+        assert!(series.to_string().starts_with("/REGULAR/TIMESERIES/FLOW/"));
+        let (first,last) = series.time_range().expect("Time range missing from condensed time-series");
+        assert!(first.to_julian_seconds() <= last.to_julian_seconds());
+        let tsc = fid.read_ts(&series,None,None,Some(false)).expect("Fail to read condensed pathname");
+        let times = tsc.times(true).unwrap();
+        assert_eq!(times.first().unwrap().to_julian_seconds(),first.to_julian_seconds());
+        assert_eq!(times.last().unwrap().to_julian_seconds(),last.to_julian_seconds());
+    }
+
+    #[test]
+    fn delete_undelete_rename_record() {
+        let file_path = String::from("data/example.dss");
+        let mut fid = HecDss::new(file_path).expect("Failed to open HEC-DSS file!");
+        let mut tsc = TimeSeriesContainer::new(TimeSeriesType::regular,3);
+        let start_date = HecTime::from_string("01MAY2023:2400",None,None).expect("Error converting date string to HecTime");
+        tsc.set_pathname(DssPathname::from_string("/REGULAR/TIMESERIES/FLOW//1Hour/Delete/"));
+        tsc.set_unit("cfs");
+        tsc.set_type("INST-VAL");
+        tsc.set_values(&[1.0,2.0,3.0]).unwrap();
+        tsc.set_times(&[start_date]).unwrap();
+        fid.put_ts(tsc,None).expect("Fail to write ts data");
+
+        let entries = fid.catalog(Some({let mut x = CatalogOptions::new();
+                                        x.set_filter(PathnamePattern::from_string("/REGULAR/TIMESERIES/FLOW/*/1Hour/Delete/").unwrap());
+                                        x})).expect("Fail to catalog dss file");
+        let record = entries[0].pathname().clone();
+        let renamed = DssPathname::from_string(&record.to_string().replace("/Delete/","/Renamed/")).unwrap();
+        fid.rename(&record,&renamed).expect("Fail to rename record");
+        let err = fid.rename(&record,&renamed).unwrap_err();
+        assert_eq!(err.kind(),&DssErrorKind::RECORD_DOES_NOT_EXIST);
+
+        fid.delete(&renamed).expect("Fail to delete record");
+        let err = fid.delete(&renamed).unwrap_err();
+        assert_eq!(err.kind(),&DssErrorKind::RECORD_DOES_NOT_EXIST);
+        match fid.undelete(&renamed) {
+            Ok(()) => {
+                let err = fid.undelete(&renamed).unwrap_err();
+                assert_eq!(err.kind(),&DssErrorKind::RECORD_ALREADY_EXISTS);
+            },
+            Err(e) => assert_eq!(e.kind(),&DssErrorKind::NO_UNDELETE_WITH_RECLAIM)
+        }
+        fid.delete_matching(PathnamePattern::from_string("/REGULAR/TIMESERIES/FLOW/*/1Hour/Renamed/").unwrap())
+           .expect("Fail to delete matching records");
+    }
+
+    #[test]
+    fn copy_records_between_files() {
+        let out_path = std::env::temp_dir().join("hecdss_copy_records.dss");
+        let _ = std::fs::remove_file(&out_path);
+        let mut fid = HecDss::new(String::from("data/example.dss")).expect("Failed to open HEC-DSS file!");
+        let mut fout = HecDss::new(out_path.to_string_lossy().into_owned()).expect("Failed to create HEC-DSS file!");
+        let dss_path = DssPathname::from_string("/PAIREDDATA/PTABLE/FREQ-FLOW///Ex2/").unwrap();
+        let copy_path = DssPathname::from_string("/PAIREDDATA/PTABLE/FREQ-FLOW///Copy/").unwrap();
+        fid.copy_to(&mut fout,dss_path,copy_path.clone()).expect("Fail to copy record");
+        let entries = fout.catalog(None).expect("Fail to catalog dss file");
+        assert!(entries.iter().any(|x| x.pathname() == &copy_path));
+        let count = fid.copy_file(&mut fout,Some(PathnamePattern::from_string("/REGULAR/*/*/*/*/*/").unwrap()))
+                       .expect("Fail to copy records");
+        assert!(count > 0);
+        assert_eq!(fout.catalog(None).unwrap().len(),count + 1);
+    }
+
+    #[test]
+    fn squeeze_file() {
+        let out_path = std::env::temp_dir().join("hecdss_squeeze.dss");
+        let _ = std::fs::remove_file(&out_path);
+        let mut fid = HecDss::new(String::from("data/example.dss")).expect("Failed to open HEC-DSS file!");
+        let mut fout = HecDss::new(out_path.to_string_lossy().into_owned()).expect("Failed to create HEC-DSS file!");
+        fid.copy_file(&mut fout,Some(PathnamePattern::from_string("/REGULAR/*/*/*/*/*/").unwrap()))
+           .expect("Fail to copy records");
+        let deleted = fout.delete_matching(PathnamePattern::from_string("/*/*/*/*/*/*/").unwrap())
+                          .expect("Fail to delete records");
+        assert!(!deleted.is_empty());
+        assert!(fout.needs_squeeze().expect("Fail to check squeeze status"));
+        fout.squeeze(true).expect("Fail to squeeze file");
+        assert!(fout.is_open());
+        assert!(!fout.needs_squeeze().unwrap());
+        assert!(fout.catalog(None).unwrap().is_empty());
+    }
+
+    #[test]
+    fn verify_file() {
+        let out_path = std::env::temp_dir().join("hecdss_verify.dss");
+        let _ = std::fs::remove_file(&out_path);
+        let mut fid = HecDss::new(String::from("data/example.dss")).expect("Failed to open HEC-DSS file!");
+        let mut fout = HecDss::new(out_path.to_string_lossy().into_owned()).expect("Failed to create HEC-DSS file!");
+        fid.copy_file(&mut fout,None).expect("Fail to copy file");
+        let report = fout.verify().expect("Fail to verify dss file");
+        assert_eq!(report.checks().len(),4);
+        assert!(report.is_ok(),"{:?}",report.issues());
+    }
+
+    #[test]
+    fn read_record_info() {
+        let file_path = String::from("data/example.dss");
+        let mut fid = HecDss::new(file_path).expect("Failed to open HEC-DSS file!");
+        let dss_path = DssPathname::from_string("/PAIREDDATA/PTABLE/FREQ-FLOW///Ex2/").unwrap();
+        let info = fid.record_info(&dss_path).expect("Fail to read record info");
+        assert!(info.record_type().is_paired_data());
+        assert!(info.ts_size().is_none());
+        let pd_size = info.pd_size().expect("Paired data sizes missing");
+        assert!(pd_size.number_ordinates() > 0 && pd_size.number_curves() > 0);
+        assert!(info.last_write_time() >= info.creation_time());
+        let dss_path = DssPathname::from_string("/PAIREDDATA/PTABLE/FREQ-FLOW///Missing/").unwrap();
+        let err = fid.record_info(&dss_path).unwrap_err();
+        assert_eq!(err.kind(),&DssErrorKind::RECORD_DOES_NOT_EXIST);
+    }
+
+    #[test]
+    fn track_changed_records() {
+        let out_path = std::env::temp_dir().join("hecdss_changes.dss");
+        let _ = std::fs::remove_file(&out_path);
+        let mut fid = HecDss::new(String::from("data/example.dss")).expect("Failed to open HEC-DSS file!");
+        let mut fout = HecDss::new(out_path.to_string_lossy().into_owned()).expect("Failed to create HEC-DSS file!");
+        let dss_path = DssPathname::from_string("/PAIREDDATA/PTABLE/FREQ-FLOW///Ex2/").unwrap();
+        fid.copy_to(&mut fout,dss_path.clone(),dss_path.clone()).expect("Fail to copy record");
+        let tracker = fout.track_changes(None,false).expect("Fail to take snapshot");
+        assert_eq!(tracker.len(),1);
+        assert!(fout.changes(&tracker).unwrap().is_empty());
+        assert!(fid.changes(&tracker).is_err());
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        let copy_path = DssPathname::from_string("/PAIREDDATA/PTABLE/FREQ-FLOW///Copy/").unwrap();
+        fid.copy_to(&mut fout,dss_path.clone(),dss_path.clone()).expect("Fail to copy record");
+        fid.copy_to(&mut fout,dss_path.clone(),copy_path.clone()).expect("Fail to copy record");
+        let changes = fout.changes(&tracker).expect("Fail to compare with snapshot");
+        assert_eq!(changes.len(),2);
+        assert!(changes.iter().any(|x| x.pathname() == &dss_path && x.kind() == ChangeKind::modified));
+        assert!(changes.iter().any(|x| x.pathname() == &copy_path && x.kind() == ChangeKind::added));
+    }
+
+    #[test]
+    fn pathname_aliases() {
+        let out_path = std::env::temp_dir().join("hecdss_aliases.dss");
+        let _ = std::fs::remove_file(&out_path);
+        let mut fid = HecDss::new(String::from("data/example.dss")).expect("Failed to open HEC-DSS file!");
+        let mut fout = HecDss::new(out_path.to_string_lossy().into_owned()).expect("Failed to create HEC-DSS file!");
+        let dss_path = DssPathname::from_string("/PAIREDDATA/PTABLE/FREQ-FLOW///Ex2/").unwrap();
+        let alias = DssPathname::from_string("/PAIREDDATA/PTABLE/FREQ-FLOW///Public/").unwrap();
+        fid.copy_to(&mut fout,dss_path.clone(),dss_path.clone()).expect("Fail to copy record");
+        fout.add_alias(&dss_path,&alias).expect("Fail to add alias");
+        assert_eq!(fout.primary_pathname(&alias).expect("Fail to get primary pathname"),dss_path);
+        assert!(fout.aliases(&dss_path).expect("Fail to list aliases").contains(&alias));
+        let err = fout.add_alias(&dss_path,&alias).unwrap_err();
+        assert_eq!(err.kind(),&DssErrorKind::RECORD_ALREADY_EXISTS);
+        fout.remove_alias(&alias).expect("Fail to remove alias");
+        assert!(!fout.aliases(&dss_path).unwrap().contains(&alias));
+    }
+
+    #[test]
+    fn write_read_paired_data() {
+        let file_path = String::from("data/example.dss");
+        let dss_path = DssPathname::from_string("/PAIREDDATA/RATING/STAGE-FLOW///Write/").unwrap();
+        let mut fid = HecDss::new(file_path).expect("Failed to open HEC-DSS file!");
+        let mut table = PairedDataTable::new(4,2);
+        table.set_pathname(dss_path.clone());
+        table.set_index(&[1.0,2.0,3.0,4.0]).unwrap();
+        table.set_columns(&[10.0,20.0,40.0,80.0,15.0,30.0,60.0,120.0]).unwrap();
+        table.set_headers(Some(&["Existing","Proposed"])).unwrap();
+        table.set_index_unit("feet");
+        table.set_index_type("UNT");
+        table.set_column_unit("cfs");
+        table.set_column_type("UNT");
+        fid.put_pd(table).expect("Fail to write paired data");
+        let table = fid.read_pd(dss_path,None).expect("Fail to read paired data");
+        assert_eq!(table.shape(),(4,2));
+        assert_eq!(table.index().as_float().unwrap(),&[1.0,2.0,3.0,4.0]);
+        assert_eq!(table.columns()[1].as_float().unwrap(),&[15.0,30.0,60.0,120.0]);
+        assert_eq!(table.index_unit(),DataUnit::feet);
+        assert_eq!(table.column_unit(),DataUnit::cfs);
+        assert_eq!(table.headers().unwrap(),&["Existing","Proposed"]);
+    }
+
+    #[test]
+    fn update_paired_data_curves() {
+        let file_path = String::from("data/example.dss");
+        let dss_path = DssPathname::from_string("/PAIREDDATA/FREQUENCY/FREQ-FLOW///Update/").unwrap();
+        let mut fid = HecDss::new(file_path).expect("Failed to open HEC-DSS file!");
+        let mut table = PairedDataTable::new(3,3);
+        table.set_pathname(dss_path.clone());
+        table.set_index(&[0.5,0.1,0.01]).unwrap();
+        table.set_index_unit("percent");
+        table.set_index_type("FREQ");
+        table.set_column_unit("cfs");
+        table.set_column_type("UNT");
+        fid.allocate_pd(table).expect("Fail to allocate paired data");
+        let mut mismatched = PairedDataSlice::new();
+        mismatched.set_col_range(2,2);
+        assert!(fid.update_pd_curves(&dss_path,mismatched,&DataValues::double(vec![100.0,200.0,300.0])).is_err());
+        let mut slice = PairedDataSlice::new();
+        slice.set_col_range(2,2);
+        fid.update_pd_curves(&dss_path,slice,&DataValues::float(vec![100.0,200.0,300.0])).expect("Fail to update curve");
+        let mut slice = PairedDataSlice::new();
+        slice.set_row_range(3,3);
+        slice.set_col_range(1,3);
+        fid.update_pd_curves(&dss_path,slice,&DataValues::float(vec![1.0,2.0,3.0])).expect("Fail to update ordinates");
+        let table = fid.read_pd(dss_path,None).expect("Fail to read paired data");
+        assert_eq!(table.index().as_float().unwrap(),&[0.5,0.1,0.01]);
+        assert_eq!(table.columns()[1].as_float().unwrap(),&[100.0,200.0,2.0]);
+        assert_eq!(table.columns()[2].as_float().unwrap()[2],3.0);
+    }
+
+    #[test]
+    fn write_read_double_paired_data() {
+        let file_path = String::from("data/example.dss");
+        let dss_path = DssPathname::from_string("/PAIREDDATA/RESERVOIR/ELEV-STORAGE///WriteDouble/").unwrap();
+        let mut fid = HecDss::new(file_path).expect("Failed to open HEC-DSS file!");
+        let mut table = PairedDataTable::new_double(3,1);
+        table.set_pathname(dss_path.clone());
+        table.set_double_index(&[100.125,101.25,102.5]).unwrap();
+        table.set_double_columns(&[1000.0001,2500.0002,4000.0003]).unwrap();
+        table.set_index_unit("feet");
+        table.set_index_type("UNT");
+        table.set_column_unit("ac-ft");
+        table.set_column_type("UNT");
+        table.set_index_is_xaxis(false);
+        table.set_precision(Some((3,4)));
+        table.set_time_zone(Some("PST"));
+        fid.put_pd(table).expect("Fail to write paired data");
+        let table = fid.read_pd(dss_path,None).expect("Fail to read paired data");
+        assert!(table.is_double());
+        assert_eq!(table.index().as_double().unwrap(),&[100.125,101.25,102.5]);
+        assert_eq!(table.columns()[0].as_double().unwrap(),&[1000.0001,2500.0002,4000.0003]);
+        assert_eq!(table.column_unit(),DataUnit::undefined("ac-ft"));
+        assert!(matches!(table.column_type(),DataType::undefined("UNT")));
+        assert!(!table.index_is_xaxis());
+        assert_eq!(table.precision(),Some((3,4)));
+        assert_eq!(table.time_zone(),Some("PST"));
+    }
+
+    #[test]
+    fn paired_data_lookup() {
+        let mut table = PairedDataTable::new(4,2);
+        table.set_index(&[1.0,2.0,4.0,8.0]).unwrap();
+        table.set_columns(&[10.0,20.0,40.0,80.0,100.0,400.0,1600.0,6400.0]).unwrap();
+        table.set_headers(Some(&["Linear","Power"])).unwrap();
+        assert_eq!(table.lookup("Linear",3.0,None).unwrap(),30.0);
+        assert_eq!(table.inverse_lookup("Linear",60.0,None).unwrap(),6.0);
+        let mut options = LookupOptions::new();
+        options.set_method(InterpolationMethod::log_log);
+        assert!((table.lookup("Power",3.0,Some(options)).unwrap() - 900.0).abs() < 1e-6);
+        assert!(table.lookup("Linear",10.0,None).is_err());
+        options.set_method(InterpolationMethod::linear);
+        options.set_out_of_range(OutOfRange::clamp);
+        assert_eq!(table.lookup("Linear",10.0,Some(options)).unwrap(),80.0);
+        options.set_out_of_range(OutOfRange::extrapolate);
+        assert_eq!(table.lookup("Linear",0.0,Some(options)).unwrap(),0.0);
+        assert!(table.lookup("Missing",1.0,None).is_err());
+    }
+
+    #[test]
+    fn edit_paired_data_columns() {
+        let file_path = String::from("data/example.dss");
+        let dss_path = DssPathname::from_string("/PAIREDDATA/PTABLE/FREQ-FLOW///Ex2/").unwrap();
+        let mut table = {
+            let mut fid = HecDss::new(file_path).expect("Failed to open HEC-DSS file!");
+            fid.read_pd(dss_path,None).expect("Fail to read paired data")
+        };
+        let (rows,cols) = table.shape();
+        let labels = table.iter_columns().map(|(label,_)| label.to_string()).collect::<Vec<_>>();
+        assert_eq!(labels.len(),cols as usize);
+        let values = if table.is_double() {
+            DataValues::double(vec![1.0;rows as usize])
+        } else {
+            DataValues::float(vec![1.0;rows as usize])
+        };
+        table.add_column("Added",values.clone()).expect("Fail to add column");
+        assert!(table.add_column("Added",values).is_err());
+        assert_eq!(table.shape(),(rows,cols+1));
+        match table.column_mut("Added").unwrap() {
+            DataValues::float(x) => x[0] = 2.0,
+            DataValues::double(x) => x[0] = 2.0
+        }
+        assert_eq!(table.column("Added").unwrap().to_double()[0],2.0);
+        let removed = table.remove_column("Added").expect("Fail to remove column");
+        assert_eq!(removed.len(),rows as usize);
+        assert!(table.column("Added").is_none());
+        assert_eq!(table.shape(),(rows,cols));
+    }
+
     #[test]
     fn read_paired_data() {
         let file_path = String::from("data/example.dss");